@@ -1,22 +1,154 @@
-use advent_of_code::safe_lines;
-use advent_of_code_2022::day1;
-use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use advent_of_code::{safe_lines, top_k::TopKExt, window::DistinctWindow};
+use aoc_2022::{
+    day1,
+    day6::marker::Marker,
+    day8::{grid::Grid, sight::Sight},
+    day_16,
+};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
-fn criterion_benchmark(c: &mut Criterion) {
-    let mut day1_lines: Vec<String> = safe_lines("res/day_1_input.txt").unwrap().collect();
+fn day1_benchmark(c: &mut Criterion) {
+    let mut day1_lines: Vec<String> = safe_lines("input/day_1.txt").unwrap().collect();
     day1_lines.extend(day1_lines.clone());
     day1_lines.extend(day1_lines.clone());
 
     let mut day1 = c.benchmark_group("day 1");
     day1.throughput(Throughput::Elements(day1_lines.len() as u64));
 
-    day1.bench_with_input("solution 1", &day1_lines.clone(), |b, i| {
-        b.iter(|| day1::solution::<100, _, _>(i.iter()))
+    for elves in [3, 100] {
+        day1.bench_with_input(BenchmarkId::new("top elves", elves), &day1_lines, |b, i| {
+            b.iter(|| day1::solution_improved(elves, i.iter()))
+        });
+    }
+}
+
+/// Keeping the largest few of a million random numbers against sorting all of them
+fn top_k_benchmark(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(43);
+    let items: Vec<u64> = (0..1_000_000).map(|_| rng.gen()).collect();
+
+    let mut top_k = c.benchmark_group("top k");
+    top_k.throughput(Throughput::Elements(items.len() as u64));
+
+    for k in [2, 100, 10_000] {
+        top_k.bench_with_input(BenchmarkId::new("collector", k), &items, |b, i| {
+            b.iter(|| i.iter().copied().top_k(k))
+        });
+        top_k.bench_with_input(BenchmarkId::new("full sort", k), &items, |b, i| {
+            b.iter(|| {
+                let mut sorted = i.clone();
+                sorted.sort_unstable_by(|a, b| b.cmp(a));
+                sorted.truncate(k);
+                sorted
+            })
+        });
+    }
+}
+
+/// Random lowercase letters, a marker of 14 is only likely to show up near the end
+fn day6_benchmark(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(6);
+    let stream: String = (0..100_000)
+        .map(|_| rng.gen_range(b'a'..=b'l') as char)
+        .chain("mnopqrstuvwxyz".chars())
+        .collect();
+
+    let mut day6 = c.benchmark_group("day 6");
+    day6.throughput(Throughput::Bytes(stream.len() as u64));
+
+    day6.bench_with_input(BenchmarkId::new("marker", 14), &stream, |b, s| {
+        b.iter(|| {
+            let mut m = Marker::new(14);
+            s.chars().position(|c| matches!(m.push(c), Ok(Some(true))))
+        })
+    });
+    day6.bench_with_input(BenchmarkId::new("hashed window", 14), &stream, |b, s| {
+        b.iter(|| DistinctWindow::new(14).windows(s.chars()).next())
+    });
+    day6.bench_with_input(BenchmarkId::new("byte window", 14), &stream, |b, s| {
+        b.iter(|| DistinctWindow::bytes(14).windows(s.bytes()).next())
+    });
+}
+
+/// Square forests of random trees, the sweeps should grow with the number of trees
+fn day8_benchmark(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(8);
+    let mut day8 = c.benchmark_group("day 8");
+
+    for size in [100, 500, 1000] {
+        let lines: Vec<String> = (0..size)
+            .map(|_| {
+                (0..size)
+                    .map(|_| char::from(rng.gen_range(b'0'..=b'9')))
+                    .collect()
+            })
+            .collect();
+        let grid = Grid::from_lines(lines.iter()).unwrap();
+
+        day8.throughput(Throughput::Elements((size * size) as u64));
+        day8.bench_with_input(BenchmarkId::new("sweeps", size), &grid, |b, g| {
+            b.iter(|| Sight::new(g))
+        });
+        day8.bench_with_input(BenchmarkId::new("most scenic", size), &grid, |b, g| {
+            b.iter(|| Sight::new(g).most_scenic())
+        });
+    }
+}
+
+/// A network shaped like the puzzle input, 60 valves in a random tree with a few extra tunnels and
+/// 15 of them worth opening
+fn day16_benchmark(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(16);
+    let name = |i: usize| {
+        let i = i as u8;
+        format!("{}{}", char::from(b'A' + i / 26), char::from(b'A' + i % 26))
+    };
+    let valves = 60;
+    let mut tunnels: Vec<Vec<usize>> = vec![Vec::new(); valves];
+    for i in 1..valves {
+        let j = rng.gen_range(0..i);
+        tunnels[i].push(j);
+        tunnels[j].push(i);
+    }
+    for _ in 0..10 {
+        let (i, j) = (rng.gen_range(0..valves), rng.gen_range(0..valves));
+        if i != j && !tunnels[i].contains(&j) {
+            tunnels[i].push(j);
+            tunnels[j].push(i);
+        }
+    }
+    let lines: Vec<String> = tunnels
+        .iter()
+        .enumerate()
+        .map(|(i, t)| {
+            let rate = if i % 4 == 1 { rng.gen_range(3..25) } else { 0 };
+            let t: Vec<String> = t.iter().map(|t| name(*t)).collect();
+            format!(
+                "Valve {} has flow rate={}; tunnels lead to valves {}",
+                name(i),
+                rate,
+                t.join(", ")
+            )
+        })
+        .collect();
+
+    let mut day16 = c.benchmark_group("day 16");
+    day16.sample_size(10);
+    day16.bench_with_input("alone", &lines, |b, l| {
+        b.iter(|| day_16::solution_pt1(l.iter()))
     });
-    day1.bench_with_input("solution 2", &day1_lines.clone(), |b, i| {
-        b.iter(|| day1::solution_improved(100, i.iter()))
+    day16.bench_with_input("with elephant", &lines, |b, l| {
+        b.iter(|| day_16::solution_pt2(l.iter()))
     });
 }
 
-criterion_group!(benches, criterion_benchmark);
+criterion_group!(
+    benches,
+    day1_benchmark,
+    day6_benchmark,
+    day8_benchmark,
+    day16_benchmark,
+    top_k_benchmark
+);
 criterion_main!(benches);
@@ -1,81 +1,33 @@
 //! Day 1 consists of counting the number of calories each elf has and finding the max.
-use std::{cmp::Reverse, collections::BinaryHeap};
+use advent_of_code::{errors::Result, top_k::TopK};
 
-use advent_of_code::errors::Result;
-
-fn attempt_push_max(arr: &mut [usize], val: usize) {
-    let mut set_max = false;
-    let mut shift_amount = 0;
-    for i in 0..arr.len() {
-        if shift_amount > arr[i] {
-            let tmp = arr[i];
-            arr[i] = shift_amount;
-            shift_amount = tmp;
-        }
-        if !set_max && val > arr[i] {
-            shift_amount = arr[i];
-            arr[i] = val;
-            set_max = true;
-        }
-    }
-}
-
-/// Solution however the time complexity could be probably improved. If E is large then the runtime
-/// is O(n * E) where n is the length of the input lines
+/// Total calories of the `E` elves carrying the most
 pub fn solution<const E: usize, S: AsRef<str>, I: Iterator<Item = S>>(lines: I) -> Result<usize> {
-    lines
-        .fold(Ok(([0; E], 0)), |accum, line| {
-            let (mut max_calories, current_calories) = accum?;
-            let line = line.as_ref().trim();
-            if line.is_empty() {
-                attempt_push_max(&mut max_calories, current_calories);
-                return Ok((max_calories, 0));
-            }
-            let calories = usize::from_str_radix(line, 10)?;
-            Ok((max_calories, current_calories + calories))
-        })
-        .map(|(mut max_calores, last_item)| {
-            if last_item > 0 {
-                attempt_push_max(&mut max_calores, last_item);
-            }
-            max_calores.iter().sum()
-        })
+    solution_improved(E, lines)
 }
 
-/// From understanding what the max was actually doing is a Binary Heap. Rust as a binary heap impl
-/// that I am going to use!
+/// Total calories of the `elves` elves carrying the most, only ever holding on to that many
+/// totals at once
 pub fn solution_improved<S: AsRef<str>, I: Iterator<Item = S>>(
     elves: usize,
     lines: I,
 ) -> Result<usize> {
-    let mut heap = BinaryHeap::new();
-    lines
-        .fold(Ok((&mut heap, 0)), |accum, line| {
-            let (heap, current_calories) = accum?;
-            let line = line.as_ref().trim();
-            if line.is_empty() {
-                heap.push(Reverse(current_calories));
-                if heap.len() > elves {
-                    heap.pop();
-                }
-                return Ok((heap, 0));
-            }
-            let calories = usize::from_str_radix(line, 10)?;
-            Ok((heap, current_calories + calories))
-        })
-        .map(|(heap, last_item)| {
-            if last_item > 0 {
-                heap.push(Reverse(last_item));
-                if heap.len() > elves {
-                    heap.pop();
-                }
-            }
-            let mut sum = 0;
-            while let Some(v) = heap.pop() {
-                sum += v.0;
-            }
-            sum
-        })
+    let mut top = TopK::new(elves);
+    let mut current_calories = 0;
+    for line in lines {
+        let line = line.as_ref().trim();
+        if line.is_empty() {
+            top.push(current_calories);
+            current_calories = 0;
+            continue;
+        }
+        current_calories += line.parse::<usize>()?;
+    }
+    if current_calories > 0 {
+        top.push(current_calories);
+    }
+
+    Ok(top.into_sorted_vec().iter().sum())
 }
 
 #[cfg(test)]
@@ -33,7 +33,13 @@
 //!
 //! In the above example, the priority of the item type that appears in both compartments of each rucksack is 16 (p), 38 (L), 42 (P), 22 (v), 20 (t), and 19 (s); the sum of these is 157.
 
-use advent_of_code::errors::{Error, Result};
+use advent_of_code::{
+    bitset::{CharSet, Priority},
+    errors::{Error, Result},
+};
+
+/// The item types in a rucksack, stored at their priority
+type Items = CharSet<Priority>;
 
 pub fn solution_pt1<S: AsRef<str>, L: Iterator<Item = S>>(lines: L) -> Result<u64> {
     lines
@@ -46,80 +52,36 @@ pub fn solution_pt1<S: AsRef<str>, L: Iterator<Item = S>>(lines: L) -> Result<u6
                 ));
             }
 
-            let v1 = encode_line(first_half)?;
-            let v2 = encode_line(second_half)?;
-            let v = decode_value(v1 & v2);
-
-            Ok(v)
+            let shared = first_half.parse::<Items>()? & second_half.parse::<Items>()?;
+            priority(shared)
         })
         .sum()
 }
 
-/// This method will take a character and encode it into a [u128].
-///
-/// This encoding is defined as the following.
-/// - If `97 <= c <= 122`
-fn encode_char(c: char) -> Result<u64> {
-    let c = c as u32;
-    let i = match c {
-        97..=122 => c - 97,
-        65..=90 => c - 65 + 26,
-        _ => {
-            return Err(Error::InvalidParseError(
-                "Not a valid encoded character".to_owned(),
-            ))
-        }
-    };
-
-    Ok(1 << i)
-}
-
-fn encode_line(line: &str) -> Result<u64> {
-    line.chars().fold(Ok(0), |accum, c| {
-        let accum = accum?;
-        let i = encode_char(c)?;
-        Ok(accum | i)
-    })
+/// Priority of the one item type in the set
+fn priority(items: Items) -> Result<u64> {
+    items
+        .indices()
+        .next()
+        .map(|p| p as u64)
+        .ok_or(Error::InvalidStruct("No item type is shared".to_owned()))
 }
 
-fn decode_value(v: u64) -> u64 {
-    let mut v = v;
-    let mut index = 1;
-    while v & 1 != 1 {
-        v = v >> 1;
-        // println!("\t{:#064b}", v);
-        index += 1;
+pub fn solution_pt2<S: AsRef<str>, L: Iterator<Item = S>>(lines: L) -> Result<u64> {
+    let mut sum = 0;
+    let mut group = Vec::with_capacity(3);
+    for line in lines {
+        group.push(line.as_ref().parse::<Items>()?);
+        if group.len() == 3 {
+            let badge = group
+                .drain(..)
+                .reduce(|accum, e| accum & e)
+                .unwrap_or_default();
+            sum += priority(badge)?;
+        }
     }
-    index
-}
 
-pub fn solution_pt2<S: AsRef<str>, L: Iterator<Item = S>>(lines: L) -> Result<u64> {
-    let mut encoded_lines = [0; 3];
-    // (sum, lines, index)
-    lines
-        .fold(Ok((0, &mut encoded_lines, 0)), |accum, line| {
-            let (mut sum, lines, mut index) = accum?;
-            if index >= 3 {
-                let k = lines.iter().fold(lines[0], |accum, e| accum & e);
-                // println!("number {:#064b}", k);
-                let value = decode_value(k);
-                sum += value;
-                index = 0;
-                lines.fill(0);
-                // println!("value: {}", value);
-            }
-            lines[index] = encode_line(line.as_ref())?;
-            // println!("{:#064b}", lines[index]);
-            Ok((sum, lines, index + 1))
-        })
-        .map(|(sum, lines, index)| {
-            if index >= 3 {
-                let k = lines.iter().fold(lines[0], |accum, e| accum & e);
-                let value = decode_value(k);
-                return sum + value;
-            }
-            sum
-        })
+    Ok(sum)
 }
 
 #[cfg(test)]
@@ -2,9 +2,7 @@ use std::{collections::VecDeque, fmt::Display, str::Chars};
 
 use advent_of_code::errors::Result;
 
-use super::r#move::Move;
-
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Cargo {
     stacks: Vec<VecDeque<char>>,
 }
@@ -20,7 +18,7 @@ impl Cargo {
     pub fn from_lines<S: AsRef<str>, I: Iterator<Item = S>>(lines: &mut I) -> Result<Self> {
         let mut stacks = Vec::new();
 
-        while let Some(line) = lines.next() {
+        for line in lines.by_ref() {
             let line = line.as_ref();
             if line.is_empty() {
                 break;
@@ -35,7 +33,7 @@ impl Cargo {
                     stacks[index].push_front(cell)
                 }
                 // space between stacks
-                if let None = chars.next() {
+                if chars.next().is_none() {
                     break;
                 }
                 index += 1;
@@ -45,91 +43,67 @@ impl Cargo {
         Ok(Cargo { stacks })
     }
 
-    pub fn apply_move(&mut self, cargo_move: &Move) {
-        if !cargo_move.is_valid(self.stacks.len()) {
-            return;
-        }
-        let (from, to) = cargo_move.get_from_to_indices();
-        let (from, to) = if from < to {
-            let (from_slice, to_slice) = self.stacks.split_at_mut(to);
-            let (_, from_slice) = from_slice.split_at_mut(from);
-            (from_slice.first_mut(), to_slice.first_mut())
-        } else {
-            let (to_slice, from_slice) = self.stacks.split_at_mut(from);
-            let (_, to_slice) = to_slice.split_at_mut(to);
-            (from_slice.first_mut(), to_slice.first_mut())
-        };
-        if let (Some(from), Some(to)) = (from, to) {
-            for _ in 0..cargo_move.amount() {
-                if let Some(v) = from.pop_back() {
-                    to.push_back(v);
-                }
-            }
-        }
+    /// Number of stacks
+    pub fn len(&self) -> usize {
+        self.stacks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stacks.is_empty()
+    }
+
+    /// The stack at `index` counting from 0, bottom crate first
+    pub fn stack(&self, index: usize) -> Option<&VecDeque<char>> {
+        self.stacks.get(index)
     }
 
-    pub fn apply_move_keep_order(&mut self, cargo_move: &Move) {
-        if !cargo_move.is_valid(self.stacks.len()) {
-            return;
+    /// Mutable access to two different stacks at once
+    pub fn stack_pair(
+        &mut self,
+        from: usize,
+        to: usize,
+    ) -> Option<(&mut VecDeque<char>, &mut VecDeque<char>)> {
+        if from == to || from.max(to) >= self.stacks.len() {
+            return None;
         }
-        let (from, to) = cargo_move.get_from_to_indices();
-        let (from, to) = if from < to {
-            let (from_slice, to_slice) = self.stacks.split_at_mut(to);
-            let (_, from_slice) = from_slice.split_at_mut(from);
-            (from_slice.first_mut(), to_slice.first_mut())
+        let (low, high) = self.stacks.split_at_mut(from.max(to));
+        let (low, high) = (&mut low[from.min(to)], &mut high[0]);
+        if from < to {
+            Some((low, high))
         } else {
-            let (to_slice, from_slice) = self.stacks.split_at_mut(from);
-            let (_, to_slice) = to_slice.split_at_mut(to);
-            (from_slice.first_mut(), to_slice.first_mut())
-        };
-        if let (Some(from), Some(to)) = (from, to) {
-            let mut from_chars = VecDeque::with_capacity(cargo_move.amount());
-            for _ in 0..cargo_move.amount() {
-                if let Some(c) = from.pop_back() {
-                    from_chars.push_front(c);
-                }
-            }
-            to.extend(from_chars);
+            Some((high, low))
         }
     }
 
+    /// The crate on top of every stack, `#` for an empty stack
     pub fn get_tops(&self) -> String {
-        let mut s = String::new();
-        for stack in self.stacks.iter() {
-            if let Some(c) = stack.get(stack.len() - 1) {
-                s.push(*c);
-            } else {
-                s.push('#');
-            }
-        }
-        s
+        self.stacks
+            .iter()
+            .map(|stack| stack.back().copied().unwrap_or('#'))
+            .collect()
     }
 }
 
+/// Draws the stacks the same way the puzzle input does
 impl Display for Cargo {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut max_stack = 0;
-        for stack in self.stacks.iter() {
-            if stack.len() > max_stack {
-                max_stack = stack.len();
-            }
-        }
+        let max_stack = self.stacks.iter().map(|s| s.len()).max().unwrap_or(0);
 
         for i in (0..max_stack).rev() {
-            for stack in self.stacks.iter() {
-                if let Some(cell) = stack.get(i) {
-                    write!(f, "[{}]", cell)?;
-                } else {
-                    write!(f, "   ")?;
-                }
-                write!(f, " ")?;
-            }
-            writeln!(f, "")?;
+            let row: Vec<String> = self
+                .stacks
+                .iter()
+                .map(|stack| match stack.get(i) {
+                    Some(cell) => format!("[{}]", cell),
+                    None => "   ".to_owned(),
+                })
+                .collect();
+            writeln!(f, "{}", row.join(" "))?;
         }
 
-        for i in 0..self.stacks.len() {
-            write!(f, " {}  ", i + 1)?;
-        }
-        Ok(())
+        let labels: Vec<String> = (1..=self.stacks.len())
+            .map(|i| format!(" {} ", i))
+            .collect();
+        write!(f, "{}", labels.join(" "))
     }
 }
@@ -0,0 +1,154 @@
+use std::collections::VecDeque;
+
+use advent_of_code::errors::{Error, Result};
+
+use super::{cargo::Cargo, r#move::Move};
+
+/// Something that can move crates from the top of one stack onto another
+pub trait Crane {
+    /// Moves the top `amount` crates of `from` onto `to`, `from` has at least that many crates
+    fn lift(&self, from: &mut VecDeque<char>, to: &mut VecDeque<char>, amount: usize);
+}
+
+/// Moves a single crate at a time, so the moved crates end up in reverse order
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CrateMover9000;
+
+/// Moves all the crates at once keeping their order
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9000 {
+    fn lift(&self, from: &mut VecDeque<char>, to: &mut VecDeque<char>, amount: usize) {
+        for _ in 0..amount {
+            if let Some(c) = from.pop_back() {
+                to.push_back(c);
+            }
+        }
+    }
+}
+
+impl Crane for CrateMover9001 {
+    fn lift(&self, from: &mut VecDeque<char>, to: &mut VecDeque<char>, amount: usize) {
+        let split = from.len() - amount;
+        to.extend(from.drain(split..));
+    }
+}
+
+/// Runs a crane over the cargo keeping a log of every move so they can be undone.
+///
+/// By default moves naming a stack that doesn't exist are skipped and moves asking for more crates
+/// than a stack has move what is there. In strict mode both are errors.
+#[derive(Debug)]
+pub struct Operator<C> {
+    cargo: Cargo,
+    crane: C,
+    strict: bool,
+    /// moves applied with the number of crates they really moved
+    log: Vec<Move>,
+    undone: Vec<Move>,
+}
+
+impl<C: Crane> Operator<C> {
+    pub fn new(cargo: Cargo, crane: C) -> Self {
+        Operator {
+            cargo,
+            crane,
+            strict: false,
+            log: Vec::new(),
+            undone: Vec::new(),
+        }
+    }
+
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
+    pub fn cargo(&self) -> &Cargo {
+        &self.cargo
+    }
+
+    /// Every move applied so far with the amount of crates it really moved
+    pub fn log(&self) -> &[Move] {
+        &self.log
+    }
+
+    pub fn apply(&mut self, cargo_move: Move) -> Result<()> {
+        let invalid = |reason: String| match cargo_move.line() {
+            Some(line) => Error::InvalidStruct(format!("Line {}: {}", line, reason)),
+            None => Error::InvalidStruct(reason),
+        };
+        if !cargo_move.is_valid(self.cargo.len()) {
+            if self.strict {
+                return Err(invalid(format!(
+                    "can't {} with {} stacks",
+                    cargo_move,
+                    self.cargo.len()
+                )));
+            }
+            return Ok(());
+        }
+
+        let (from, to) = cargo_move.get_from_to_indices();
+        let available = self.cargo.stack(from).map_or(0, |s| s.len());
+        if available < cargo_move.amount() && self.strict {
+            return Err(invalid(format!(
+                "can't {} when stack {} only has {} crates",
+                cargo_move,
+                cargo_move.from(),
+                available
+            )));
+        }
+        let amount = available.min(cargo_move.amount());
+        let cargo_move = cargo_move.with_amount(amount);
+        self.lift(from, to, cargo_move.amount());
+        self.log.push(cargo_move);
+        self.undone.clear();
+        Ok(())
+    }
+
+    /// Takes back the last move, moving the crates back with the same crane puts them back the way
+    /// they were
+    pub fn undo(&mut self) -> Option<&Move> {
+        let last = self.log.pop()?;
+        let (from, to) = last.get_from_to_indices();
+        self.lift(to, from, last.amount());
+        self.undone.push(last);
+        self.undone.last()
+    }
+
+    /// Applies the last undone move again
+    pub fn redo(&mut self) -> Option<&Move> {
+        let next = self.undone.pop()?;
+        let (from, to) = next.get_from_to_indices();
+        self.lift(from, to, next.amount());
+        self.log.push(next);
+        self.log.last()
+    }
+
+    /// Applies every logged move in order to some other cargo, e.g. a copy of the starting one
+    pub fn replay(&self, cargo: &mut Cargo) -> Result<()> {
+        for m in self.log.iter() {
+            let (from, to) = m.get_from_to_indices();
+            let (from, to) = cargo
+                .stack_pair(from, to)
+                .ok_or_else(|| Error::InvalidStruct(format!("Can't replay {}", m)))?;
+            if from.len() < m.amount() {
+                return Err(Error::InvalidStruct(format!("Can't replay {}", m)));
+            }
+            self.crane.lift(from, to, m.amount());
+        }
+        Ok(())
+    }
+
+    pub fn into_cargo(self) -> Cargo {
+        self.cargo
+    }
+
+    fn lift(&mut self, from: usize, to: usize, amount: usize) {
+        if let Some((from, to)) = self.cargo.stack_pair(from, to) {
+            self.crane.lift(from, to, amount);
+        }
+    }
+}
@@ -20,54 +20,64 @@
 //! move 1 from 1 to 2
 //!```
 
+use std::cell::Cell;
+
 use advent_of_code::errors::Result;
 
 use crate::day5::r#move::Move;
 
-use self::cargo::Cargo;
-mod cargo;
-mod r#move;
+use self::{
+    cargo::Cargo,
+    crane::{Crane, CrateMover9000, CrateMover9001, Operator},
+};
+pub mod cargo;
+pub mod crane;
+pub mod r#move;
 
-pub fn solution_pt1<S: AsRef<str>, I: Iterator<Item = S>>(mut lines: I) -> Result<String> {
-    // Parse cargo
-    let mut cargo = Cargo::from_lines(&mut lines)?;
-    println!("{}", cargo);
-    // Parse moves
-    // Apply moves
-    while let Some(line) = lines.next() {
-        let line = line.as_ref().trim();
-        if line.is_empty() {
-            break;
-        }
-        let m: Move = line.parse()?;
-        cargo.apply_move(&m);
-    }
-    // Gather top cargo
-    Ok(cargo.get_tops())
+pub fn solution_pt1<S: AsRef<str>, I: Iterator<Item = S>>(lines: I) -> Result<String> {
+    Ok(rearrange(lines, CrateMover9000)?.cargo().get_tops())
+}
+
+pub fn solution_pt2<S: AsRef<str>, I: Iterator<Item = S>>(lines: I) -> Result<String> {
+    Ok(rearrange(lines, CrateMover9001)?.cargo().get_tops())
 }
 
-pub fn solution_pt2<S: AsRef<str>, I: Iterator<Item = S>>(mut lines: I) -> Result<String> {
+/// Parses the drawing and runs every move on it with `crane`, failing on the first move that
+/// can't be done
+pub fn rearrange<S, I, C>(lines: I, crane: C) -> Result<Operator<C>>
+where
+    S: AsRef<str>,
+    I: Iterator<Item = S>,
+    C: Crane,
+{
+    let line_number = Cell::new(0);
+    let mut lines = lines.inspect(|_| line_number.set(line_number.get() + 1));
     // Parse cargo
-    let mut cargo = Cargo::from_lines(&mut lines)?;
+    let cargo = Cargo::from_lines(&mut lines)?;
     println!("{}", cargo);
-    // Parse moves
+    let mut operator = Operator::new(cargo, crane).strict();
     // Apply moves
-    while let Some(line) = lines.next() {
+    for line in lines.by_ref() {
         let line = line.as_ref().trim();
         if line.is_empty() {
             break;
         }
         let m: Move = line.parse()?;
-        cargo.apply_move_keep_order(&m);
+        operator.apply(m.at_line(line_number.get()))?;
     }
-    // Gather top cargo
-    Ok(cargo.get_tops())
+    Ok(operator)
 }
 
 #[cfg(test)]
 mod tests {
 
-    use super::{solution_pt1, solution_pt2};
+    use advent_of_code::errors::Error;
+
+    use super::{
+        cargo::Cargo,
+        crane::{CrateMover9000, CrateMover9001, Operator},
+        rearrange, solution_pt1, solution_pt2,
+    };
 
     const PAGE_EXAMPLE: &str = r#"
     [D]    
@@ -102,4 +112,69 @@ move 1 from 1 to 2
 
         assert_eq!(res, "MCD".to_owned());
     }
+
+    #[test]
+    fn renders_drawing() {
+        let mut lines = PAGE_EXAMPLE.split('\n').skip(1);
+        let cargo = Cargo::from_lines(&mut lines).unwrap();
+        let drawing: Vec<&str> = PAGE_EXAMPLE.lines().skip(1).take(4).collect();
+
+        assert_eq!(cargo.to_string(), drawing.join("\n"));
+        assert_eq!(
+            Cargo::from_lines(&mut cargo.to_string().lines()).unwrap(),
+            cargo
+        );
+    }
+
+    #[test]
+    fn strict_moves() {
+        let invalid = PAGE_EXAMPLE.replace("move 2 from 2 to 1", "move 2 from 2 to 4");
+        let err = rearrange(invalid.split('\n').skip(1), CrateMover9000).unwrap_err();
+        assert_eq!(
+            err,
+            Error::InvalidStruct("Line 8: can't move 2 from 2 to 4 with 3 stacks".to_owned())
+        );
+
+        let underflow = PAGE_EXAMPLE.replace("move 3 from 1 to 3", "move 4 from 1 to 3");
+        let err = rearrange(underflow.split('\n').skip(1), CrateMover9001).unwrap_err();
+        assert_eq!(
+            err,
+            Error::InvalidStruct(
+                "Line 7: can't move 4 from 1 to 3 when stack 1 only has 3 crates".to_owned()
+            )
+        );
+    }
+
+    #[test]
+    fn lenient_moves() {
+        let mut lines = PAGE_EXAMPLE.split('\n').skip(1);
+        let cargo = Cargo::from_lines(&mut lines).unwrap();
+        let mut operator = Operator::new(cargo, CrateMover9000);
+        for m in ["move 1 from 4 to 1", "move 5 from 1 to 2"] {
+            operator.apply(m.parse().unwrap()).unwrap();
+        }
+
+        assert_eq!(operator.log().len(), 1);
+        assert_eq!(operator.log()[0].amount(), 2);
+        assert_eq!(operator.cargo().get_tops(), "#ZP");
+    }
+
+    #[test]
+    fn undo_and_replay() {
+        let mut lines = PAGE_EXAMPLE.split('\n').skip(1);
+        let start = Cargo::from_lines(&mut lines).unwrap();
+        let mut operator = rearrange(PAGE_EXAMPLE.split('\n').skip(1), CrateMover9000).unwrap();
+
+        let mut replayed = start.clone();
+        operator.replay(&mut replayed).unwrap();
+        assert_eq!(&replayed, operator.cargo());
+
+        while operator.undo().is_some() {}
+        assert_eq!(operator.cargo(), &start);
+        assert_eq!(
+            operator.redo().map(|m| m.to_string()),
+            Some("move 1 from 2 to 1".to_owned())
+        );
+        assert_eq!(operator.cargo().get_tops(), "DCP");
+    }
 }
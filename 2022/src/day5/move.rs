@@ -1,12 +1,14 @@
-use std::str::FromStr;
+use std::{fmt::Display, str::FromStr};
 
 use advent_of_code::errors::Error;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Move {
     from: usize,
     to: usize,
     amount: usize,
+    /// where the move was read from, for error messages
+    line: Option<usize>,
 }
 
 impl Move {
@@ -28,6 +30,36 @@ impl Move {
     pub fn amount(&self) -> usize {
         self.amount
     }
+
+    /// Stack the crates are taken from, counting from 1
+    pub fn from(&self) -> usize {
+        self.from
+    }
+
+    /// Stack the crates are put on, counting from 1
+    pub fn to(&self) -> usize {
+        self.to
+    }
+
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
+
+    pub fn at_line(mut self, line: usize) -> Self {
+        self.line = Some(line);
+        self
+    }
+
+    pub fn with_amount(mut self, amount: usize) -> Self {
+        self.amount = amount;
+        self
+    }
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "move {} from {} to {}", self.amount, self.from, self.to)
+    }
 }
 
 impl FromStr for Move {
@@ -58,6 +90,11 @@ impl FromStr for Move {
         let to = white_space.next().ok_or_else(ended_to_soon)?;
         let to = usize::from_str(to)?;
 
-        Ok(Move { from, to, amount })
+        Ok(Move {
+            from,
+            to,
+            amount,
+            line: None,
+        })
     }
 }
@@ -1,62 +1,63 @@
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::VecDeque;
 
+use advent_of_code::{
+    bitset::{CharSet, Lowercase},
+    errors::Result,
+};
+
+/// Detects a window of `size` different lowercase letters, see
+/// [`DistinctWindow`](advent_of_code::window::DistinctWindow) for other items and duplicates.
 pub struct Marker {
     data: VecDeque<char>,
-    map: HashMap<char, usize>,
+    /// letters seen an odd number of times in `data`
+    odd: CharSet<Lowercase>,
     size: usize,
-    count: usize,
 }
 
 impl Marker {
     pub fn new(size: usize) -> Self {
         Marker {
-            data: VecDeque::new(),
-            map: HashMap::new(),
+            data: VecDeque::with_capacity(size + 1),
+            odd: CharSet::new(),
             size,
-            count: 0,
         }
     }
 
-    fn push_insert(&mut self, c: char) {
+    /// Pushes the character into the marker. Returns `None` for when the marker hasn't been filled
+    /// yet, `Some(true)` for when it is filled and every character is different and `Some(false)`
+    /// otherwise.
+    ///
+    /// Each push is `O(1)`: a letter is toggled in and out of the set as it enters and leaves, and
+    /// the set can only be as big as the marker when no letter shows up twice. Characters other
+    /// than lowercase letters are an error and leave the marker as it was.
+    pub fn push(&mut self, c: char) -> Result<Option<bool>> {
+        self.odd.toggle(c)?;
         self.data.push_back(c);
-        if let Some(count) = self.map.get_mut(&c) {
-            *count += 1;
-        } else {
-            self.map.insert(c, 1);
+        if self.data.len() > self.size {
+            if let Some(old) = self.data.pop_front() {
+                // only letters that could be toggled are ever in `data`
+                self.odd.toggle(old)?;
+            }
         }
-        self.count += 1;
-    }
-
-    /// Pushes the character into the marker. Returns `Some(true)` for when there is a duplicate. `None`
-    /// for when the marker hasn't been filled yet. `Some(false)` for when it is filled and there
-    /// isn't a duplicate
-    ///
-    /// For runtime this method on each character costs: `O(hash(c))` since the operations done are
-    /// `O(1)` for insert into linked list, removal from linked list, grabbing from hashmap,
-    /// removing from hashmap, inserting into hashmap.
-    pub fn push(&mut self, c: char) -> Option<bool> {
-        // println!(
-        //     "c = {} --> {:?} : {:?} : {}",
-        //     c, self.data, self.map, self.count
-        // );
         if self.data.len() < self.size {
-            self.push_insert(c);
-            return None;
-        }
-        if let Some(old) = self.data.pop_front() {
-            let mut should_remove = false;
-            if let Some(count) = self.map.get_mut(&old) {
-                if *count - 1 == 0 {
-                    should_remove = true;
-                }
-                *count -= 1;
-                self.count -= 1;
-            }
-            if should_remove {
-                self.map.remove(&old);
-            }
+            return Ok(None);
         }
-        self.push_insert(c);
-        Some(self.map.len() == self.count)
+        Ok(Some(self.odd.len() == self.size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Marker;
+
+    #[test]
+    fn letters_only() {
+        let mut marker = Marker::new(3);
+        let pushed: Vec<Option<bool>> = "abca".chars().map(|c| marker.push(c).unwrap()).collect();
+        assert_eq!(pushed, vec![None, None, Some(true), Some(true)]);
+
+        assert!(marker.push('A').is_err());
+        // the rejected character didn't take a place in the window
+        assert_eq!(marker.push('a').unwrap(), Some(false));
     }
 }
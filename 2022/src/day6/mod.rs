@@ -1,15 +1,23 @@
-use self::marker::Marker;
+use advent_of_code::{
+    errors::{Error, Result},
+    window::DistinctWindow,
+};
 
-mod marker;
+pub mod marker;
 
-pub fn solution(length: usize, data_stream: &str) -> Option<usize> {
-    let mut m = Marker::new(length);
-    data_stream
-        .chars()
-        .enumerate()
-        .skip_while(|(_, c)| !matches!(m.push(*c), Some(true)))
+/// Number of characters read when the first `length` all different characters have been seen
+pub fn solution(length: usize, data_stream: &str) -> Result<usize> {
+    DistinctWindow::bytes(length)
+        .windows(data_stream.bytes())
         .next()
-        .map(|(i, _)| i + 1)
+        .map(|start| start + length)
+        .ok_or_else(|| {
+            Error::InvalidStruct(format!(
+                "No marker of {} different characters in {} characters",
+                length,
+                data_stream.len()
+            ))
+        })
 }
 
 #[cfg(test)]
@@ -51,4 +59,10 @@ mod tests {
             assert_eq!(actual, expected)
         }
     }
+
+    #[test]
+    fn no_marker() {
+        assert!(solution(4, "abcabcabc").is_err());
+        assert!(solution(4, "abc").is_err());
+    }
 }
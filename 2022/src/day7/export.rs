@@ -0,0 +1,337 @@
+use std::fmt::Display;
+
+use advent_of_code::errors::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+use super::system::{FileSystem, NodeId};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortBy {
+    #[default]
+    Name,
+    /// largest first, equal sizes by name
+    Size,
+}
+
+/// Draws a file system like the `tree` command does
+///
+/// ```text
+/// /
+/// ├── a (94853)
+/// │   └── e (584)
+/// └── b.txt (14848514)
+/// ```
+#[derive(Clone, Copy)]
+pub struct Tree<'a> {
+    fs: &'a FileSystem,
+    root: NodeId,
+    sort: SortBy,
+    max_depth: Option<usize>,
+    human_readable: bool,
+}
+
+impl<'a> Tree<'a> {
+    pub fn new(fs: &'a FileSystem) -> Self {
+        Tree {
+            fs,
+            root: FileSystem::ROOT,
+            sort: SortBy::default(),
+            max_depth: None,
+            human_readable: false,
+        }
+    }
+
+    /// Only draws `root` and what is inside it
+    pub fn from(mut self, root: NodeId) -> Self {
+        self.root = root;
+        self
+    }
+
+    pub fn sort_by(mut self, sort: SortBy) -> Self {
+        self.sort = sort;
+        self
+    }
+
+    /// Stops drawing `depth` levels below the root
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Shows sizes like `14.2M` instead of every byte
+    pub fn human_readable(mut self) -> Self {
+        self.human_readable = true;
+        self
+    }
+
+    fn sorted_children(&self, id: NodeId) -> Vec<NodeId> {
+        let mut children = self.fs.node(id).children().to_vec();
+        let name = |c: &NodeId| self.fs.node(*c).name();
+        match self.sort {
+            SortBy::Name => children.sort_by(|a, b| name(a).cmp(name(b))),
+            SortBy::Size => children.sort_by(|a, b| {
+                let size = |c: &NodeId| self.fs.node(*c).size();
+                size(b).cmp(&size(a)).then_with(|| name(a).cmp(name(b)))
+            }),
+        }
+        children
+    }
+
+    fn size(&self, id: NodeId) -> String {
+        let size = self.fs.node(id).size();
+        if self.human_readable {
+            human_size(size)
+        } else {
+            size.to_string()
+        }
+    }
+}
+
+impl Display for Tree<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{} ({})",
+            self.fs.node(self.root).name(),
+            self.size(self.root)
+        )?;
+
+        // (node, depth below the root, prefix drawn before it, whether it is the last child)
+        let mut stack: Vec<(NodeId, usize, String, bool)> = Vec::new();
+        let push_children = |stack: &mut Vec<_>, id: NodeId, depth: usize, prefix: &str| {
+            let children = self.sorted_children(id);
+            let last = children.len().saturating_sub(1);
+            // pushed in reverse so the first child comes off the stack first
+            for (i, c) in children.into_iter().enumerate().rev() {
+                stack.push((c, depth, prefix.to_owned(), i == last));
+            }
+        };
+        if self.max_depth != Some(0) {
+            push_children(&mut stack, self.root, 1, "");
+        }
+
+        while let Some((id, depth, prefix, last)) = stack.pop() {
+            let branch = if last { "└── " } else { "├── " };
+            let node = self.fs.node(id);
+            writeln!(f, "{}{}{} ({})", prefix, branch, node.name(), self.size(id))?;
+
+            if self.max_depth.is_none_or(|max| depth < max) {
+                let prefix = format!("{}{}", prefix, if last { "    " } else { "│   " });
+                push_children(&mut stack, id, depth + 1, &prefix);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Size in bytes with a binary unit, one decimal place once it is past 1024
+pub fn human_size(size: usize) -> String {
+    const UNITS: [&str; 5] = ["K", "M", "G", "T", "P"];
+    if size < 1024 {
+        return size.to_string();
+    }
+    let mut value = size as f64 / 1024.0;
+    let mut unit = 0;
+    // anything that would round up to 1024.0 is shown in the next unit
+    while value >= 1023.95 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1}{}", value, UNITS[unit])
+}
+
+/// A node as it is written in JSON. Files have no children and directories have children with
+/// their size being the total of them.
+#[derive(Debug, Serialize, Deserialize)]
+struct JsonNode {
+    name: String,
+    size: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    children: Option<Vec<JsonNode>>,
+}
+
+impl FileSystem {
+    pub fn tree(&self) -> Tree<'_> {
+        Tree::new(self)
+    }
+
+    /// Nested JSON of every node with children sorted by name, so two snapshots can be diffed
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.json_node(FileSystem::ROOT))
+            .expect("file systems always serialize")
+    }
+
+    fn json_node(&self, id: NodeId) -> JsonNode {
+        let node = self.node(id);
+        JsonNode {
+            name: node.name().to_owned(),
+            size: node.size(),
+            children: node.is_dir().then(|| {
+                Tree::new(self)
+                    .sorted_children(id)
+                    .into_iter()
+                    .map(|c| self.json_node(c))
+                    .collect()
+            }),
+        }
+    }
+
+    /// Reads back a snapshot made by [`FileSystem::to_json`]. Fails if a directory's size isn't
+    /// the total of what is in it.
+    pub fn from_json(json: &str) -> Result<FileSystem> {
+        let root: JsonNode =
+            serde_json::from_str(json).map_err(|e| Error::InvalidParseError(e.to_string()))?;
+        let children = root.children.ok_or(Error::InvalidStruct(
+            "The root has to be a directory".to_owned(),
+        ))?;
+
+        let mut fs = FileSystem::new();
+        let mut expected = vec![(FileSystem::ROOT, root.size)];
+        let mut stack: Vec<(NodeId, JsonNode)> = children
+            .into_iter()
+            .map(|c| (FileSystem::ROOT, c))
+            .collect();
+        while let Some((parent, node)) = stack.pop() {
+            match node.children {
+                None => {
+                    fs.add_file(parent, node.name, node.size)?;
+                }
+                Some(children) => {
+                    let dir = fs.add_directory(parent, node.name)?;
+                    expected.push((dir, node.size));
+                    stack.extend(children.into_iter().map(|c| (dir, c)));
+                }
+            }
+        }
+
+        if let Some((dir, size)) = expected.into_iter().find(|(d, s)| fs.node(*d).size() != *s) {
+            return Err(Error::InvalidStruct(format!(
+                "{} should be {} but its contents add up to {}",
+                fs.path(dir),
+                size,
+                fs.node(dir).size()
+            )));
+        }
+        Ok(fs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{human_size, SortBy};
+    use crate::day7::system::FileSystem;
+
+    fn page_example() -> FileSystem {
+        let mut fs = FileSystem::new();
+        let a = fs.add_directory(FileSystem::ROOT, "a").unwrap();
+        fs.add_file(FileSystem::ROOT, "b.txt", 14848514).unwrap();
+        fs.add_file(FileSystem::ROOT, "c.dat", 8504156).unwrap();
+        let d = fs.add_directory(FileSystem::ROOT, "d").unwrap();
+        let e = fs.add_directory(a, "e").unwrap();
+        fs.add_file(a, "f", 29116).unwrap();
+        fs.add_file(a, "g", 2557).unwrap();
+        fs.add_file(a, "h.lst", 62596).unwrap();
+        fs.add_file(e, "i", 584).unwrap();
+        for (name, size) in [
+            ("j", 4060174),
+            ("d.log", 8033020),
+            ("d.ext", 5626152),
+            ("k", 7214296),
+        ] {
+            fs.add_file(d, name, size).unwrap();
+        }
+        fs
+    }
+
+    #[test]
+    fn tree_by_name() {
+        let fs = page_example();
+
+        let expected = "\
+/ (48381165)
+├── a (94853)
+│   ├── e (584)
+│   │   └── i (584)
+│   ├── f (29116)
+│   ├── g (2557)
+│   └── h.lst (62596)
+├── b.txt (14848514)
+├── c.dat (8504156)
+└── d (24933642)
+    ├── d.ext (5626152)
+    ├── d.log (8033020)
+    ├── j (4060174)
+    └── k (7214296)
+";
+        assert_eq!(fs.to_string(), expected);
+    }
+
+    #[test]
+    fn tree_by_size() {
+        let fs = page_example();
+        let tree = fs
+            .tree()
+            .sort_by(SortBy::Size)
+            .max_depth(1)
+            .human_readable();
+
+        let expected = "\
+/ (46.1M)
+├── d (23.8M)
+├── b.txt (14.2M)
+├── c.dat (8.1M)
+└── a (92.6K)
+";
+        assert_eq!(tree.to_string(), expected);
+        assert_eq!(fs.tree().max_depth(0).to_string(), "/ (48381165)\n");
+    }
+
+    #[test]
+    fn tree_from_directory() {
+        let fs = page_example();
+        let a = fs.resolve(FileSystem::ROOT, "/a").unwrap();
+
+        let expected = "\
+a (94853)
+├── e (584)
+├── f (29116)
+├── g (2557)
+└── h.lst (62596)
+";
+        assert_eq!(fs.tree().from(a).max_depth(1).to_string(), expected);
+    }
+
+    #[test]
+    fn human_sizes() {
+        assert_eq!(human_size(584), "584");
+        assert_eq!(human_size(1024), "1.0K");
+        assert_eq!(human_size(1048575), "1.0M");
+        assert_eq!(human_size(1048063), "1023.5K");
+        assert_eq!(human_size(14848514), "14.2M");
+        assert_eq!(human_size(3 << 30), "3.0G");
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let fs = page_example();
+
+        let json = fs.to_json();
+        let read = FileSystem::from_json(&json).unwrap();
+
+        assert_eq!(read.to_string(), fs.to_string());
+        assert_eq!(read.to_json(), json);
+    }
+
+    #[test]
+    fn json_sizes_are_checked() {
+        let json = r#"{"name": "/", "size": 10, "children": [
+            {"name": "a", "size": 5, "children": [{"name": "x", "size": 4}]},
+            {"name": "y", "size": 6}
+        ]}"#;
+
+        let err = FileSystem::from_json(json).unwrap_err();
+        assert!(format!("{:?}", err).contains("/a should be 5 but its contents add up to 4"));
+        assert!(FileSystem::from_json(r#"{"name": "/", "size": 1}"#).is_err());
+        assert!(FileSystem::from_json("not json").is_err());
+    }
+}
@@ -1,86 +1,41 @@
 use self::{
-    system::{FSIterType, FileSystem, SystemType},
-    term::Command,
+    shell::Shell,
+    system::{FSIterType, FileSystem},
 };
 use advent_of_code::errors::Result;
 
+mod export;
+mod shell;
 mod system;
 mod term;
 
 fn build_file_system<S: AsRef<str>, L: Iterator<Item = S>>(lines: L) -> Result<FileSystem> {
-    let mut root = FileSystem::new_directory("/");
-    let mut current = root.clone();
-
-    for line in lines {
-        let line = line.as_ref().trim();
-        if line.is_empty() {
-            continue;
-        }
-        let cmd: Result<Command> = line.parse();
-        let output: Result<FileSystem> = line.parse();
-        match (cmd, output) {
-            (Err(_), Ok(line)) => current.add_file_system(line)?,
-            (Ok(cmd), Err(_)) => match cmd {
-                Command::Cd(name) => {
-                    current = match name.as_ref() {
-                        ".." => current.parent()?,
-                        "/" => root.clone(),
-                        s => current.find_dir(s)?.ok_or("No such directory found")?,
-                    }
-                }
-                // do nothing because it is going to start reading
-                Command::Ls => {}
-            },
-            (Err(e), Err(_)) => return Err(e),
-            _ => Err("Invalid Input")?,
-        };
-    }
-    root.update_sizes()?;
-    Ok(root)
+    let mut shell = Shell::new();
+    shell.run_all(lines)?;
+    Ok(shell.into_file_system())
 }
 
 pub fn solution_pt1<S: AsRef<str>, L: Iterator<Item = S>>(lines: L) -> Result<usize> {
     let fs = build_file_system(lines)?;
-    println!("File System: \n{}", fs);
 
-    fs.iter(FSIterType::BreadthFirst)
-        .map(|v| {
-            let size = v.size()?;
-            if size <= 100_000 && matches!(v.system_type()?, SystemType::Directory(_, _)) {
-                Ok(size)
-            } else {
-                Ok(0)
-            }
-        })
-        .sum()
+    Ok(fs
+        .iter(FSIterType::BreadthFirst)
+        .filter(|v| v.is_dir() && v.size() <= 100_000)
+        .map(|v| v.size())
+        .sum())
 }
 
 pub fn solution_pt2<S: AsRef<str>, L: Iterator<Item = S>>(lines: L) -> Result<usize> {
     let fs = build_file_system(lines)?;
-    println!("File System: \n{}", fs);
 
-    let unused = 70_000_000 - fs.size()?;
-    let need_space = 30_000_000 - unused;
+    let unused = 70_000_000usize.saturating_sub(fs.size());
+    let need_space = 30_000_000usize.saturating_sub(unused);
 
     fs.iter(FSIterType::BreadthFirst)
-        .filter(|v| {
-            v.system_type()
-                .map(|s| matches!(s, SystemType::Directory(_, _)))
-                .unwrap_or(false)
-        })
-        .fold(Ok(None), |accum, v| {
-            let min = accum?;
-            let size = v.size()?;
-            if size >= need_space {
-                println!("{}", v.with_depth(0));
-            }
-            Ok(match min {
-                None => Some(size),
-                Some(min) if size >= need_space && size < min => Some(size),
-                Some(min) => Some(min),
-            })
-        })
-        .and_then(|min| min.ok_or("Minimum not found".into()))
+        .filter(|v| v.is_dir() && v.size() >= need_space)
+        .min_by_key(|v| v.size())
+        .map(|v| v.size())
+        .ok_or("Minimum not found".into())
 }
 
 #[cfg(test)]
@@ -0,0 +1,261 @@
+use advent_of_code::errors::{Error, Result};
+
+use super::{
+    system::{FileSystem, NodeId},
+    term::{Command, Listing},
+};
+
+/// Replays a terminal transcript onto a [`FileSystem`], checking it makes sense as it goes.
+///
+/// A transcript is inconsistent when `ls` shows a file again with a different size or as a
+/// directory, `cd` goes into a directory no `ls` has shown or output shows up without an `ls`.
+/// Errors say which line of the transcript was the problem.
+#[derive(Debug, Clone)]
+pub struct Shell {
+    fs: FileSystem,
+    cwd: NodeId,
+    /// lines run so far
+    line: usize,
+    /// whether output lines are expected, only right after `ls`
+    listing: bool,
+}
+
+impl Shell {
+    pub fn new() -> Self {
+        Shell {
+            fs: FileSystem::new(),
+            cwd: FileSystem::ROOT,
+            line: 0,
+            listing: false,
+        }
+    }
+
+    pub fn fs(&self) -> &FileSystem {
+        &self.fs
+    }
+
+    pub fn into_file_system(self) -> FileSystem {
+        self.fs
+    }
+
+    /// Current working directory
+    pub fn cwd(&self) -> NodeId {
+        self.cwd
+    }
+
+    /// Runs one line of the transcript, either a `$` command or `ls` output. Returns what the
+    /// command prints, only `du` prints anything.
+    pub fn run_line(&mut self, line: &str) -> Result<Option<String>> {
+        self.line += 1;
+        let line = line.trim();
+        if line.is_empty() {
+            return Ok(None);
+        }
+        let result = if line.starts_with('$') {
+            line.parse().and_then(|cmd| self.run(cmd))
+        } else if self.listing {
+            line.parse().and_then(|l| self.list(l)).map(|_| None)
+        } else {
+            Err(Error::InvalidStruct(format!(
+                "{} isn't a command or ls output",
+                line
+            )))
+        };
+        result.map_err(|e| at_line(self.line, e))
+    }
+
+    /// Runs every line, stopping at the first error
+    pub fn run_all<S: AsRef<str>, L: Iterator<Item = S>>(&mut self, lines: L) -> Result<()> {
+        for line in lines {
+            self.run_line(line.as_ref())?;
+        }
+        Ok(())
+    }
+
+    pub fn run(&mut self, cmd: Command) -> Result<Option<String>> {
+        self.listing = matches!(cmd, Command::Ls);
+        match cmd {
+            Command::Cd(path) => {
+                self.cwd = self
+                    .fs
+                    .resolve(self.cwd, &path)
+                    .filter(|d| self.fs.node(*d).is_dir())
+                    .ok_or_else(|| {
+                        Error::InvalidStruct(format!("cd into {} which hasn't been listed", path))
+                    })?;
+            }
+            Command::Ls => {}
+            Command::Mkdir(path) => {
+                let (parent, name) = self.parent_of(&path)?;
+                self.fs.add_directory(parent, name)?;
+            }
+            Command::Rm(path) => {
+                let target = self.existing(&path)?;
+                if self.fs.ancestors(self.cwd).any(|a| a == target) {
+                    return Err(Error::InvalidStruct(format!(
+                        "Can't remove {} while inside it",
+                        path
+                    )));
+                }
+                self.fs.remove(target)?;
+            }
+            Command::Mv(from, to) => {
+                let target = self.existing(&from)?;
+                match self.fs.resolve(self.cwd, &to) {
+                    Some(dir) if self.fs.node(dir).is_dir() => {
+                        let name = self.fs.node(target).name().to_owned();
+                        self.fs.move_to(target, dir, &name)?;
+                    }
+                    Some(_) => {
+                        return Err(Error::InvalidStruct(format!("{} already exists", to)));
+                    }
+                    None => {
+                        let (dir, name) = self.parent_of(&to)?;
+                        self.fs.move_to(target, dir, name)?;
+                    }
+                }
+            }
+            Command::Du(path) => {
+                let target = match path {
+                    Some(path) => self.existing(&path)?,
+                    None => self.cwd,
+                };
+                return Ok(Some(format!(
+                    "{}\t{}",
+                    self.fs.node(target).size(),
+                    self.fs.path(target)
+                )));
+            }
+        }
+        Ok(None)
+    }
+
+    fn list(&mut self, listing: Listing) -> Result<()> {
+        let (name, size) = match &listing {
+            Listing::Directory(name) => (name, None),
+            Listing::File(name, size) => (name, Some(*size)),
+        };
+        let Some(seen) = self.fs.find_child(self.cwd, name) else {
+            self.fs.add_listing(self.cwd, listing)?;
+            return Ok(());
+        };
+
+        let seen = self.fs.node(seen);
+        match (seen.is_dir(), size) {
+            (true, None) => Ok(()),
+            (false, Some(size)) if size == seen.size() => Ok(()),
+            (false, Some(size)) => Err(Error::InvalidStruct(format!(
+                "{} listed with size {} but was {} before",
+                self.fs.path(seen.id()),
+                size,
+                seen.size()
+            ))),
+            _ => Err(Error::InvalidStruct(format!(
+                "{} listed as both a file and a directory",
+                self.fs.path(seen.id())
+            ))),
+        }
+    }
+
+    fn existing(&self, path: &str) -> Result<NodeId> {
+        self.fs
+            .resolve(self.cwd, path)
+            .ok_or_else(|| Error::InvalidStruct(format!("No such file or directory {}", path)))
+    }
+
+    /// The existing directory a new node at `path` would go in and the node's name
+    fn parent_of<'p>(&self, path: &'p str) -> Result<(NodeId, &'p str)> {
+        let (dir, name) = match path.trim_end_matches('/').rsplit_once('/') {
+            Some(("", name)) => (Some(FileSystem::ROOT), name),
+            Some((dir, name)) => (self.fs.resolve(self.cwd, dir), name),
+            None => (Some(self.cwd), path),
+        };
+        match dir {
+            Some(dir) if self.fs.node(dir).is_dir() && !matches!(name, "" | "." | "..") => {
+                Ok((dir, name))
+            }
+            _ => Err(Error::InvalidStruct(format!("Can't create {}", path))),
+        }
+    }
+}
+
+impl Default for Shell {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn at_line(line: usize, e: Error) -> Error {
+    match e {
+        Error::InvalidParseError(s) => Error::InvalidParseError(format!("Line {}: {}", line, s)),
+        Error::InvalidStruct(s) => Error::InvalidStruct(format!("Line {}: {}", line, s)),
+        Error::RawError(s) => Error::RawError(format!("Line {}: {}", line, s)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use advent_of_code::errors::Error;
+
+    use super::Shell;
+
+    fn run(transcript: &str) -> Result<Shell, Error> {
+        let mut shell = Shell::new();
+        shell.run_all(transcript.lines())?;
+        Ok(shell)
+    }
+
+    #[test]
+    fn paths_and_edits() {
+        let mut shell = run("$ cd /\n$ mkdir a\n$ mkdir a/b\n$ cd /a/b\n$ ls\n10 f\n").unwrap();
+
+        assert_eq!(shell.fs().path(shell.cwd()), "/a/b");
+        shell.run_line("$ mv f ../g").unwrap();
+        shell.run_line("$ cd ..").unwrap();
+        assert_eq!(
+            shell.run_line("$ du b").unwrap(),
+            Some("0\t/a/b".to_owned())
+        );
+        assert_eq!(shell.run_line("$ du").unwrap(), Some("10\t/a".to_owned()));
+        shell.run_line("$ mv /a/g b").unwrap();
+        shell.run_line("$ rm b/g").unwrap();
+        assert_eq!(shell.run_line("$ du /").unwrap(), Some("0\t/".to_owned()));
+        assert!(shell.run_line("$ rm /a").is_err());
+    }
+
+    #[test]
+    fn relisting_is_fine() {
+        let shell = run("$ ls\ndir a\n5 b\n$ ls\n5 b\ndir a\n").unwrap();
+
+        assert_eq!(shell.fs().size(), 5);
+    }
+
+    #[test]
+    fn inconsistent_transcripts() {
+        let cases = [
+            (
+                "$ ls\n5 b\n$ ls\n6 b\n",
+                "Line 4: /b listed with size 6 but was 5 before",
+            ),
+            (
+                "$ ls\ndir a\n\n$ cd b\n",
+                "Line 4: cd into b which hasn't been listed",
+            ),
+            (
+                "$ ls\ndir a\n$ ls\n3 a\n",
+                "Line 4: /a listed as both a file and a directory",
+            ),
+            ("$ cd /\n5 b\n", "Line 2: 5 b isn't a command or ls output"),
+        ];
+        for (transcript, message) in cases {
+            assert_eq!(
+                run(transcript).unwrap_err(),
+                Error::InvalidStruct(message.to_owned())
+            );
+        }
+        assert_eq!(
+            run("$ ls\n$ pwd\n").unwrap_err(),
+            Error::InvalidParseError("Line 2: Invalid command pwd".to_owned())
+        );
+    }
+}
@@ -1,283 +1,452 @@
-use std::{
-    cell::RefCell,
-    collections::VecDeque,
-    fmt::Display,
-    marker::PhantomData,
-    rc::{Rc, Weak},
-    str::FromStr,
-};
-
-use advent_of_code::errors::Error;
-
-#[derive(Clone)]
+use std::{collections::VecDeque, fmt::Display};
+
+use advent_of_code::errors::{Error, Result};
+
+use super::term::Listing;
+
+/// Index of a node in its [`FileSystem`]
+pub type NodeId = usize;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SystemType {
-    File(String),
-    Directory(String, Vec<FileSystem>),
+    File,
+    Directory(Vec<NodeId>),
 }
 
-struct FS {
+#[derive(Debug, Clone)]
+pub struct Node {
+    id: NodeId,
+    name: String,
+    parent: Option<NodeId>,
     system_type: SystemType,
-    size: Option<usize>,
-    parent: Option<Weak<RefCell<FS>>>,
+    /// size of the file or everything inside the directory
+    size: usize,
 }
 
-#[derive(Clone)]
-pub struct FileSystem(Rc<RefCell<FS>>);
+impl Node {
+    pub fn id(&self) -> NodeId {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn parent(&self) -> Option<NodeId> {
+        self.parent
+    }
+
+    pub fn system_type(&self) -> &SystemType {
+        &self.system_type
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_dir(&self) -> bool {
+        matches!(self.system_type, SystemType::Directory(_))
+    }
 
-macro_rules! write_with_tabs {
-    ($dst:expr, $d:ident, $($arg:tt)*) => {
-        for _ in 0..$d {
-            write!($dst, "\t")?;
+    pub fn children(&self) -> &[NodeId] {
+        match &self.system_type {
+            SystemType::File => &[],
+            SystemType::Directory(children) => children,
         }
-        write!($dst, $($arg)*)?;
     }
 }
 
+/// Tree of files and directories stored in one `Vec`, nodes point at their parent and children
+/// by index. The root directory `/` is always [`FileSystem::ROOT`].
+#[derive(Debug, Clone)]
+pub struct FileSystem {
+    nodes: Vec<Node>,
+}
+
 impl FileSystem {
-    fn new(fs: FS) -> Self {
-        FileSystem(Rc::new(RefCell::new(fs)))
-    }
-
-    pub fn new_file<S: ToString>(name: S, size: usize) -> FileSystem {
-        Self::new(FS {
-            system_type: SystemType::File(name.to_string()),
-            size: Some(size),
-            parent: None,
-        })
-    }
-
-    pub fn new_directory<S: ToString>(name: S) -> FileSystem {
-        Self::new(FS {
-            system_type: SystemType::Directory(name.to_string(), Vec::new()),
-            size: None,
-            parent: None,
-        })
-    }
-
-    /// Adds a file system to the children of `self`
-    ///
-    /// Note: the self is `&mut self` this is to ensure that users of this function must have this
-    /// `FileSystem` as a mutable reference. It will work without the mutablility marker but this
-    /// actually does change the inner reference. This should be reflected here.
-    pub fn add_file_system(&mut self, fs: FileSystem) -> Result<(), Error> {
-        let mut from_fs = self.0.try_borrow_mut()?;
-
-        match &mut from_fs.system_type {
-            SystemType::File(_) => Err(Error::InvalidStruct(
-                "can't add sub file to a file".to_owned(),
-            )),
-            SystemType::Directory(_, children) => {
-                // This has to be scoped so that the borrow gets freed and the to can be pushed.
-                {
-                    let mut to_fs = fs.0.try_borrow_mut()?;
-                    to_fs.parent = Some(Rc::downgrade(&self.0));
-                }
-                children.push(fs);
-                Ok(())
-            }
+    pub const ROOT: NodeId = 0;
+
+    pub fn new() -> Self {
+        FileSystem {
+            nodes: vec![Node {
+                id: Self::ROOT,
+                name: "/".to_owned(),
+                parent: None,
+                system_type: SystemType::Directory(Vec::new()),
+                size: 0,
+            }],
         }
     }
 
-    pub fn parent(&self) -> Result<FileSystem, Error> {
-        Ok(FileSystem(
-            self.0
-                .try_borrow()?
-                .parent
-                .as_ref()
-                .ok_or("No parent on file system")?
-                .upgrade()
-                .ok_or(Error::BorrowError("unable to get parent".to_string()))?,
-        ))
-    }
-
-    pub fn find_dir(&self, s: &str) -> Result<Option<FileSystem>, Error> {
-        let current = self.0.try_borrow()?;
-        match &current.system_type {
-            SystemType::File(_) => Err(Error::RawError("No directories in a file".to_owned())),
-            SystemType::Directory(_, children) => {
-                for c in children.iter() {
-                    let child = c.0.try_borrow()?;
-                    let found = match &child.system_type {
-                        SystemType::File(name) if name == s => true,
-                        SystemType::Directory(name, _) if name == s => true,
-                        _ => false,
-                    };
-                    if found {
-                        return Ok(Some(FileSystem(c.0.clone())));
-                    }
-                }
-                Ok(None)
-            }
+    pub fn node(&self, id: NodeId) -> &Node {
+        &self.nodes[id]
+    }
+
+    /// Whether the node can still be reached from the root, removed nodes and everything in them
+    /// can't
+    pub fn contains(&self, id: NodeId) -> bool {
+        id < self.nodes.len() && self.ancestors(id).last() == Some(Self::ROOT)
+    }
+
+    fn check(&self, id: NodeId) -> Result<()> {
+        match self.contains(id) {
+            true => Ok(()),
+            false => Err(Error::InvalidStruct(format!("No node {}", id))),
         }
     }
 
-    pub fn ref_counts(&self) -> (usize, usize) {
-        (Rc::strong_count(&self.0), Rc::weak_count(&self.0))
+    /// Number of files and directories ever added, including the root
+    pub fn len(&self) -> usize {
+        self.nodes.len()
     }
 
-    pub fn with_depth<'a>(&'a self, depth: usize) -> DepthFileSystem {
-        DepthFileSystem(depth, self)
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
     }
 
-    fn write_with_depth(&self, f: &mut std::fmt::Formatter<'_>, depth: isize) -> std::fmt::Result {
-        let mut queue = VecDeque::new();
-        queue.push_back((self.clone(), 0));
+    pub fn add_file<S: ToString>(
+        &mut self,
+        parent: NodeId,
+        name: S,
+        size: usize,
+    ) -> Result<NodeId> {
+        self.add_node(parent, name.to_string(), SystemType::File, size)
+    }
 
-        while let Some((current, d)) = queue.pop_back() {
-            let current = current.0.borrow();
-            match &current.system_type {
-                SystemType::File(name) => {
-                    write_with_tabs!(f, d, "File: {}", name);
-                }
-                SystemType::Directory(name, children) => {
-                    write_with_tabs!(f, d, "Directory {}", name);
-                    if depth < 0 || d + 1 <= depth {
-                        for child in children.iter() {
-                            queue.push_back((child.clone(), d + 1))
-                        }
-                    }
-                }
-            };
-            writeln!(f, " Size: {:?}", current.size)?;
-        }
+    pub fn add_directory<S: ToString>(&mut self, parent: NodeId, name: S) -> Result<NodeId> {
+        self.add_node(
+            parent,
+            name.to_string(),
+            SystemType::Directory(Vec::new()),
+            0,
+        )
+    }
 
-        Ok(())
+    /// Adds a line of `ls` output to the children of `parent`
+    pub fn add_listing(&mut self, parent: NodeId, listing: Listing) -> Result<NodeId> {
+        match listing {
+            Listing::Directory(name) => self.add_directory(parent, name),
+            Listing::File(name, size) => self.add_file(parent, name, size),
+        }
     }
 
-    pub fn update_sizes(&mut self) -> Result<(), Error> {
-        self.update_size_rec()?;
-        Ok(())
-        // TODO: Figure out how to do non recursive strategy
-        // let mut queue = VecDeque::new();
-        // queue.push_back(self.clone());
-        //
-        //
-        // while let Some(current) = queue.pop_back() {
-        //     let cur = current.0.try_borrow()?;
-        //     if let (Some(parent), Some(cur_size)) = (&cur.parent, cur.size) {
-        //         let parent = parent
-        //             .upgrade()
-        //             .ok_or(Error::BorrowError("can't upgrade parent".to_owned()))?;
-        //         let mut parent = parent.try_borrow_mut()?;
-        //
-        //         if let Some(size) = parent.size.as_mut() {
-        //             *size += cur_size
-        //         } else {
-        //             parent.size = Some(cur_size)
-        //         }
-        //     }
-        //     match &cur.system_type {
-        //         SystemType::File(_) => {}
-        //         SystemType::Directory(_, children) => {
-        //             for c in children.iter() {
-        //                 queue.push_back(c.clone())
-        //             }
-        //         }
-        //     }
-        // }
-    }
-
-    fn update_size_rec(&mut self) -> Result<usize, Error> {
-        let mut fs = self.0.try_borrow_mut()?;
-        match &mut fs.system_type {
-            SystemType::File(_) => fs.size.ok_or("No Size for File Invalid".into()),
-            SystemType::Directory(_, children) => {
-                let mut total = 0;
-                for c in children.iter_mut() {
-                    total += c.update_size_rec()?;
-                }
-                fs.size = Some(total);
-                Ok(total)
+    fn add_node(
+        &mut self,
+        parent: NodeId,
+        name: String,
+        system_type: SystemType,
+        size: usize,
+    ) -> Result<NodeId> {
+        self.check(parent)?;
+        if self.find_child(parent, &name).is_some() {
+            return Err(Error::InvalidStruct(format!(
+                "{} already exists in {}",
+                name,
+                self.path(parent)
+            )));
+        }
+        let id = self.nodes.len();
+        match &mut self.nodes[parent].system_type {
+            SystemType::Directory(children) => children.push(id),
+            SystemType::File => {
+                return Err(Error::InvalidStruct(
+                    "can't add sub file to a file".to_owned(),
+                ))
             }
         }
+        self.nodes.push(Node {
+            id,
+            name,
+            parent: Some(parent),
+            system_type,
+            size: 0,
+        });
+        self.grow(id, size);
+        Ok(id)
     }
 
-    pub fn iter<'a>(&'a self, iter_type: FSIterType) -> FSIterator<'a> {
-        FSIterator {
-            iter_type,
-            queue: VecDeque::from([self.clone()]),
-            _queue_phantom: Default::default(),
+    /// Adds `size` to the node and every directory above it
+    fn grow(&mut self, id: NodeId, size: usize) {
+        let mut current = Some(id);
+        while let Some(id) = current {
+            self.nodes[id].size += size;
+            current = self.nodes[id].parent;
         }
     }
 
-    pub fn size(&self) -> Result<usize, Error> {
-        self.0
-            .try_borrow()
-            .map(|s| s.size.unwrap_or(0))
-            .map_err(|e| e.into())
+    /// Takes `size` off the node and every directory above it
+    fn shrink(&mut self, id: NodeId, size: usize) {
+        let mut current = Some(id);
+        while let Some(id) = current {
+            self.nodes[id].size -= size;
+            current = self.nodes[id].parent;
+        }
     }
 
-    pub fn system_type(&self) -> Result<SystemType, Error> {
-        let sys_type = self.0.try_borrow()?;
-        Ok(sys_type.system_type.clone())
+    /// Takes the node out of its parent directory, it keeps its own children
+    fn detach(&mut self, id: NodeId) -> Result<NodeId> {
+        let parent = self.nodes[id]
+            .parent
+            .ok_or(Error::InvalidStruct("The root can't be moved".to_owned()))?;
+        if let SystemType::Directory(children) = &mut self.nodes[parent].system_type {
+            children.retain(|c| *c != id);
+        }
+        self.shrink(parent, self.nodes[id].size);
+        self.nodes[id].parent = None;
+        Ok(parent)
     }
-}
 
-pub struct DepthFileSystem<'a>(usize, &'a FileSystem);
+    /// Removes the node and everything in it. Its id stays taken but it can't be reached from the
+    /// root anymore
+    pub fn remove(&mut self, id: NodeId) -> Result<()> {
+        self.check(id)?;
+        self.detach(id).map(|_| ())
+    }
 
-impl<'a> Display for DepthFileSystem<'a> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.1.write_with_depth(f, self.0 as isize)
+    /// Moves the node into the directory `to` and renames it `name`
+    pub fn move_to(&mut self, id: NodeId, to: NodeId, name: &str) -> Result<()> {
+        self.check(id)?;
+        self.check(to)?;
+        if !self.nodes[to].is_dir() {
+            return Err(Error::InvalidStruct(format!(
+                "Can't move {} into {}",
+                id, to
+            )));
+        }
+        if self.ancestors(to).any(|a| a == id) {
+            return Err(Error::InvalidStruct(format!(
+                "Can't move {} inside itself",
+                self.path(id)
+            )));
+        }
+        if self.find_child(to, name).is_some_and(|c| c != id) {
+            return Err(Error::InvalidStruct(format!(
+                "{} already exists in {}",
+                name,
+                self.path(to)
+            )));
+        }
+
+        self.detach(id)?;
+        self.nodes[id].name = name.to_owned();
+        self.nodes[id].parent = Some(to);
+        if let SystemType::Directory(children) = &mut self.nodes[to].system_type {
+            children.push(id);
+        }
+        self.grow(to, self.nodes[id].size);
+        Ok(())
     }
-}
 
-impl Display for FileSystem {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.write_with_depth(f, -1)
+    /// The node followed by every directory above it up to the root
+    pub fn ancestors(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        std::iter::successors(Some(id), |id| self.nodes[*id].parent)
     }
-}
 
-impl FromStr for FileSystem {
-    type Err = Error;
+    /// Follows a `/` separated path from `from`, paths starting with `/` start from the root.
+    /// `..` goes up a directory and `.` stays put.
+    pub fn resolve(&self, from: NodeId, path: &str) -> Option<NodeId> {
+        let start = if path.starts_with('/') {
+            Self::ROOT
+        } else {
+            from
+        };
+        path.split('/')
+            .try_fold(start, |current, segment| match segment {
+                "" | "." => Some(current),
+                ".." => Some(self.parent(current).unwrap_or(Self::ROOT)),
+                name => self.find_child(current, name),
+            })
+    }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut splits = s.split_whitespace();
-        let file_system_too_short = || Error::InvalidParseError("file system too short".to_owned());
-        let indicator = splits.next().ok_or_else(file_system_too_short)?;
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.nodes.get(id)?.parent
+    }
 
-        match indicator {
-            "dir" => {
-                let name = splits.next().ok_or_else(file_system_too_short)?;
-                Ok(Self::new_directory(name.to_owned()))
-            }
-            size => {
-                let size = usize::from_str(size)?;
-                let name = splits.next().ok_or_else(file_system_too_short)?;
-                Ok(Self::new_file(name.to_owned(), size))
-            }
+    /// The file or directory called `name` directly inside `id`
+    pub fn find_child(&self, id: NodeId, name: &str) -> Option<NodeId> {
+        self.nodes
+            .get(id)?
+            .children()
+            .iter()
+            .copied()
+            .find(|c| self.nodes[*c].name == name)
+    }
+
+    pub fn find_dir(&self, id: NodeId, name: &str) -> Option<NodeId> {
+        self.find_child(id, name)
+            .filter(|c| self.nodes[*c].is_dir())
+    }
+
+    /// Absolute path of the node, directories don't end with a `/` except the root
+    pub fn path(&self, id: NodeId) -> String {
+        let mut names: Vec<&str> = self
+            .ancestors(id)
+            .filter(|id| *id != Self::ROOT)
+            .map(|id| self.nodes[id].name.as_str())
+            .collect();
+        names.reverse();
+        format!("/{}", names.join("/"))
+    }
+
+    /// Total size of everything
+    pub fn size(&self) -> usize {
+        self.nodes[Self::ROOT].size
+    }
+
+    /// Every node from the root down
+    pub fn iter(&self, iter_type: FSIterType) -> FSIterator<'_> {
+        self.iter_from(Self::ROOT, iter_type)
+    }
+
+    /// `id` and every node below it
+    pub fn iter_from(&self, id: NodeId, iter_type: FSIterType) -> FSIterator<'_> {
+        FSIterator {
+            fs: self,
+            iter_type,
+            queue: VecDeque::from([id]),
         }
     }
 }
 
+impl Default for FileSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Display for FileSystem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.tree())
+    }
+}
+
 pub enum FSIterType {
     DepthFirst,
     BreadthFirst,
 }
 
 pub struct FSIterator<'a> {
+    fs: &'a FileSystem,
     iter_type: FSIterType,
-    queue: VecDeque<FileSystem>,
-    _queue_phantom: PhantomData<&'a FileSystem>,
+    queue: VecDeque<NodeId>,
 }
 
 impl<'a> Iterator for FSIterator<'a> {
-    //TODO: figure out how to make it &'a FileSystem
-    type Item = FileSystem;
+    type Item = &'a Node;
 
     fn next(&mut self) -> Option<Self::Item> {
         let next = match self.iter_type {
             FSIterType::DepthFirst => self.queue.pop_back(),
             FSIterType::BreadthFirst => self.queue.pop_front(),
         }?;
-        match &next.0.try_borrow().ok()?.system_type {
-            SystemType::File(_) => {}
-            SystemType::Directory(_, children) => {
-                for c in children.iter() {
-                    self.queue.push_back(c.clone())
-                }
-            }
-        };
+        let node = &self.fs.nodes[next];
+        self.queue.extend(node.children());
+
+        Some(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use advent_of_code::errors::Error;
+
+    use super::{FSIterType, FileSystem};
+
+    fn sample() -> FileSystem {
+        let mut fs = FileSystem::new();
+        let a = fs.add_directory(FileSystem::ROOT, "a").unwrap();
+        let e = fs.add_directory(a, "e").unwrap();
+        fs.add_file(e, "i", 584).unwrap();
+        fs.add_file(a, "f", 29116).unwrap();
+        fs.add_file(FileSystem::ROOT, "b.txt", 14848514).unwrap();
+        fs
+    }
+
+    #[test]
+    fn sizes_and_paths() {
+        let fs = sample();
+        let e = fs
+            .find_dir(fs.find_dir(FileSystem::ROOT, "a").unwrap(), "e")
+            .unwrap();
+
+        assert_eq!(fs.size(), 14848514 + 29116 + 584);
+        assert_eq!(fs.node(e).size(), 584);
+        assert_eq!(fs.path(e), "/a/e");
+        assert_eq!(fs.path(FileSystem::ROOT), "/");
+        assert_eq!(fs.find_dir(FileSystem::ROOT, "b.txt"), None);
+    }
+
+    #[test]
+    fn iterators() {
+        let fs = sample();
+        let names = |t| fs.iter(t).map(|n| n.name()).collect::<Vec<_>>();
+
+        assert_eq!(
+            names(FSIterType::BreadthFirst),
+            vec!["/", "a", "b.txt", "e", "f", "i"]
+        );
+        assert_eq!(
+            names(FSIterType::DepthFirst),
+            vec!["/", "b.txt", "a", "f", "e", "i"]
+        );
+    }
+
+    #[test]
+    fn no_children_for_files() {
+        let mut fs = sample();
+        let b = fs.find_child(FileSystem::ROOT, "b.txt").unwrap();
+
+        assert!(fs.add_file(b, "nope", 1).is_err());
+        assert!(fs.add_directory(FileSystem::ROOT, "b.txt").is_err());
+    }
+
+    #[test]
+    fn resolve_paths() {
+        let fs = sample();
+        let e = fs.resolve(FileSystem::ROOT, "a/e").unwrap();
+
+        assert_eq!(fs.path(e), "/a/e");
+        assert_eq!(fs.resolve(e, "../../b.txt"), fs.resolve(e, "/b.txt"));
+        assert_eq!(fs.resolve(e, "./i").map(|i| fs.node(i).size()), Some(584));
+        assert_eq!(fs.resolve(e, "/nope"), None);
+    }
+
+    #[test]
+    fn remove_and_move() {
+        let mut fs = sample();
+        let a = fs.resolve(FileSystem::ROOT, "a").unwrap();
+        let e = fs.resolve(a, "e").unwrap();
+
+        assert!(fs.move_to(a, e, "a").is_err());
+        fs.move_to(e, FileSystem::ROOT, "moved").unwrap();
+        assert_eq!(fs.path(e), "/moved");
+        assert_eq!(fs.node(a).size(), 29116);
+        assert_eq!(fs.size(), 14848514 + 29116 + 584);
+
+        fs.remove(a).unwrap();
+        assert_eq!(fs.size(), 14848514 + 584);
+        assert_eq!(fs.resolve(FileSystem::ROOT, "a/f"), None);
+        assert!(fs.remove(FileSystem::ROOT).is_err());
+    }
 
-        Some(next.clone())
+    #[test]
+    fn removed_nodes() {
+        let mut fs = sample();
+        let a = fs.resolve(FileSystem::ROOT, "a").unwrap();
+        let e = fs.resolve(a, "e").unwrap();
+        let b = fs.resolve(FileSystem::ROOT, "b.txt").unwrap();
+
+        fs.remove(a).unwrap();
+        let no_node = |id| Some(Error::InvalidStruct(format!("No node {}", id)));
+        assert_eq!(fs.remove(a).err(), no_node(a));
+        assert_eq!(fs.move_to(a, FileSystem::ROOT, "a").err(), no_node(a));
+        // everything inside a removed directory is gone as well
+        assert!(!fs.contains(e));
+        assert_eq!(fs.remove(e).err(), no_node(e));
+        assert_eq!(fs.add_file(e, "new", 10).err(), no_node(e));
+        assert_eq!(fs.add_directory(a, "new").err(), no_node(a));
+        assert_eq!(fs.move_to(b, a, "b.txt").err(), no_node(a));
+        assert_eq!(fs.path(b), "/b.txt");
+        assert_eq!(fs.size(), 14848514);
     }
 }
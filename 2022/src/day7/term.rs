@@ -1,9 +1,16 @@
 use advent_of_code::errors::Error;
 use std::str::FromStr;
 
+#[derive(Debug, PartialEq, Eq)]
 pub enum Command {
     Cd(String),
     Ls,
+    Mkdir(String),
+    Rm(String),
+    /// from, to
+    Mv(String, String),
+    /// defaults to the current directory
+    Du(Option<String>),
 }
 
 impl FromStr for Command {
@@ -17,13 +24,39 @@ impl FromStr for Command {
             return Err(Error::InvalidParseError("Command starts with $".to_owned()));
         }
 
-        match splits.next().ok_or_else(cmd_to_short)? {
+        let mut arg = || splits.next().map(|a| a.to_owned()).ok_or_else(cmd_to_short);
+
+        match arg()?.as_str() {
             "ls" => Ok(Self::Ls),
-            "cd" => {
-                let dir = splits.next().ok_or_else(cmd_to_short)?;
-                Ok(Self::Cd(dir.trim().to_owned()))
-            }
-            _ => Err(Error::InvalidParseError("Invalid command".to_owned())),
+            "cd" => Ok(Self::Cd(arg()?)),
+            "mkdir" => Ok(Self::Mkdir(arg()?)),
+            "rm" => Ok(Self::Rm(arg()?)),
+            "mv" => Ok(Self::Mv(arg()?, arg()?)),
+            "du" => Ok(Self::Du(arg().ok())),
+            c => Err(Error::InvalidParseError(format!("Invalid command {}", c))),
+        }
+    }
+}
+
+/// A line printed by `ls`
+#[derive(Debug, PartialEq, Eq)]
+pub enum Listing {
+    Directory(String),
+    File(String, usize),
+}
+
+impl FromStr for Listing {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut splits = s.split_whitespace();
+        let listing_too_short = || Error::InvalidParseError("listing too short".to_owned());
+        let indicator = splits.next().ok_or_else(listing_too_short)?;
+        let name = splits.next().ok_or_else(listing_too_short)?.to_owned();
+
+        match indicator {
+            "dir" => Ok(Self::Directory(name)),
+            size => Ok(Self::File(name, usize::from_str(size)?)),
         }
     }
 }
@@ -1,3 +1,4 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Direction {
     Left,
     Right,
@@ -6,26 +7,63 @@ pub enum Direction {
 }
 
 impl Direction {
-    pub fn iter<'a>(
-        &'a self,
-        start_point: (usize, usize),
-        max: (usize, usize),
-    ) -> DirectionIterator<'a> {
+    pub const ALL: [Direction; 4] = [
+        Direction::Left,
+        Direction::Right,
+        Direction::Up,
+        Direction::Down,
+    ];
+
+    pub fn iter(self, start_point: (usize, usize), max: (usize, usize)) -> DirectionIterator {
         DirectionIterator {
             direction: self,
             cur_point: start_point,
             max,
         }
     }
+
+    pub fn opposite(self) -> Direction {
+        match self {
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+        }
+    }
+
+    /// The cells along the edge the direction points at, one for every row or column
+    pub fn edge(self, max: (usize, usize)) -> impl Iterator<Item = (usize, usize)> {
+        let (height, width) = max;
+        let count = match self {
+            Direction::Left | Direction::Right => height,
+            Direction::Up | Direction::Down => width,
+        };
+        (0..count).map(move |i| match self {
+            Direction::Left => (i, 0),
+            Direction::Right => (i, width - 1),
+            Direction::Up => (0, i),
+            Direction::Down => (height - 1, i),
+        })
+    }
+
+    /// Every row or column starting from the edge the direction points at and going away from it
+    pub fn lines(
+        self,
+        max: (usize, usize),
+    ) -> impl Iterator<Item = impl Iterator<Item = (usize, usize)>> {
+        let away = self.opposite();
+        self.edge(max)
+            .map(move |start| std::iter::once(start).chain(away.iter(start, max)))
+    }
 }
 
-pub struct DirectionIterator<'a> {
-    direction: &'a Direction,
+pub struct DirectionIterator {
+    direction: Direction,
     cur_point: (usize, usize),
     max: (usize, usize),
 }
 
-impl<'a> Iterator for DirectionIterator<'a> {
+impl Iterator for DirectionIterator {
     type Item = (usize, usize);
 
     fn next(&mut self) -> Option<Self::Item> {
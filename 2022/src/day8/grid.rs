@@ -1,6 +1,5 @@
 use std::fmt::Display;
 
-use super::Direction;
 use advent_of_code::errors::{Error, Result};
 
 pub struct Grid {
@@ -29,6 +28,14 @@ impl Grid {
             .get(0)
             .map(|r| r.len())
             .ok_or("Grid must have at least one row")?;
+        if let Some(r) = grid.iter().position(|row| row.len() != width) {
+            return Err(Error::InvalidStruct(format!(
+                "Row {} has {} trees but the first row has {}",
+                r,
+                grid[r].len(),
+                width
+            )));
+        }
 
         Ok(Grid {
             grid,
@@ -41,28 +48,9 @@ impl Grid {
         (self.height, self.width)
     }
 
-    /// Doesn't do any bounds checking because I am too lazy today ;)
-    pub fn is_visible(&self, start: (usize, usize), direction: Direction) -> bool {
-        let height = self.grid[start.0][start.1];
-
-        for (r, c) in direction.iter(start, (self.height, self.width)) {
-            if self.grid[r][c] >= height {
-                return false;
-            }
-        }
-        true
-    }
-
-    pub fn viewing_distance(&self, start: (usize, usize), direction: Direction) -> usize {
-        let height = self.grid[start.0][start.1];
-        let mut dist = 0;
-        for (r, c) in direction.iter(start, (self.height, self.width)) {
-            dist += 1;
-            if self.grid[r][c] >= height {
-                break;
-            }
-        }
-        dist
+    /// Height of the tree at `(row, column)`
+    pub fn get(&self, pos: (usize, usize)) -> Option<u8> {
+        self.grid.get(pos.0)?.get(pos.1).copied()
     }
 }
 
@@ -74,4 +62,3 @@ impl Display for Grid {
         Ok(())
     }
 }
-
@@ -0,0 +1,169 @@
+use std::fmt::Display;
+
+use super::{direction::Direction, grid::Grid};
+
+/// What can be seen from and of every tree in the forest, worked out with one sweep per direction
+/// over every row or column
+pub struct Sight {
+    dims: (usize, usize),
+    /// whether the tree can be seen from outside the forest from any side
+    visible: Vec<Vec<bool>>,
+    /// number of trees seen from each tree looking in each direction, in [`Direction::ALL`] order
+    distances: Vec<Vec<[usize; 4]>>,
+}
+
+impl Sight {
+    /// Sweeps each line of trees keeping a stack of the trees still in view, tallest at the
+    /// bottom. Every tree pops the smaller trees it hides so a line takes linear time.
+    pub fn new(grid: &Grid) -> Self {
+        let (height, width) = grid.dims();
+        let mut visible = vec![vec![false; width]; height];
+        let mut distances = vec![vec![[0; 4]; width]; height];
+
+        // (tree height, index along the line)
+        let mut stack: Vec<(u8, usize)> = Vec::new();
+        for (d, direction) in Direction::ALL.into_iter().enumerate() {
+            for line in direction.lines(grid.dims()) {
+                stack.clear();
+                for (i, (r, c)) in line.enumerate() {
+                    let tree = grid.get((r, c)).unwrap_or_default();
+                    while stack.last().is_some_and(|(t, _)| *t < tree) {
+                        stack.pop();
+                    }
+                    match stack.last() {
+                        Some((_, blocker)) => distances[r][c][d] = i - blocker,
+                        None => {
+                            visible[r][c] = true;
+                            distances[r][c][d] = i;
+                        }
+                    }
+                    stack.push((tree, i));
+                }
+            }
+        }
+
+        Sight {
+            dims: (height, width),
+            visible,
+            distances,
+        }
+    }
+
+    pub fn is_visible(&self, pos: (usize, usize)) -> Option<bool> {
+        self.visible.get(pos.0)?.get(pos.1).copied()
+    }
+
+    /// Number of trees seen from `pos` looking in `direction` up to the first one at least as tall
+    pub fn viewing_distance(&self, pos: (usize, usize), direction: Direction) -> Option<usize> {
+        let d = Direction::ALL.iter().position(|a| *a == direction)?;
+        Some(self.distances.get(pos.0)?.get(pos.1)?[d])
+    }
+
+    pub fn scenic_score(&self, pos: (usize, usize)) -> Option<usize> {
+        Some(self.distances.get(pos.0)?.get(pos.1)?.iter().product())
+    }
+
+    /// Scenic score of every tree
+    pub fn scenic_scores(&self) -> Vec<Vec<usize>> {
+        self.distances
+            .iter()
+            .map(|row| row.iter().map(|d| d.iter().product()).collect())
+            .collect()
+    }
+
+    /// Whether every tree can be seen from outside the forest
+    pub fn visibility(&self) -> &[Vec<bool>] {
+        &self.visible
+    }
+
+    pub fn visible_count(&self) -> usize {
+        self.visible.iter().flatten().filter(|v| **v).count()
+    }
+
+    /// Position and score of the tree with the best scenic score, the first one in reading order
+    /// if there is a tie
+    pub fn most_scenic(&self) -> Option<((usize, usize), usize)> {
+        let (height, width) = self.dims;
+        (0..height)
+            .flat_map(|r| (0..width).map(move |c| (r, c)))
+            .filter_map(|pos| Some((pos, self.scenic_score(pos)?)))
+            .rev()
+            .max_by_key(|(_, score)| *score)
+    }
+}
+
+/// `#` for the trees that can be seen from outside the forest and `.` for hidden ones
+impl Display for Sight {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.visible.iter() {
+            for v in row {
+                write!(f, "{}", if *v { '#' } else { '.' })?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Sight;
+    use crate::day8::{direction::Direction, grid::Grid};
+
+    const PAGE_EXAMPLE: &str = "30373\n25512\n65332\n33549\n35390";
+
+    /// Walks a ray from the tree for each direction
+    fn ray(grid: &Grid, pos: (usize, usize), direction: Direction) -> (bool, usize) {
+        let tree = grid.get(pos).unwrap();
+        let mut dist = 0;
+        for p in direction.iter(pos, grid.dims()) {
+            dist += 1;
+            if grid.get(p).unwrap() >= tree {
+                return (false, dist);
+            }
+        }
+        (true, dist)
+    }
+
+    #[test]
+    fn page_example() {
+        let grid = Grid::from_lines(PAGE_EXAMPLE.lines()).unwrap();
+        let sight = Sight::new(&grid);
+
+        assert_eq!(sight.visible_count(), 21);
+        assert_eq!(sight.most_scenic(), Some(((3, 2), 8)));
+        assert_eq!(sight.viewing_distance((1, 2), Direction::Up), Some(1));
+        assert_eq!(sight.viewing_distance((1, 2), Direction::Right), Some(2));
+        assert_eq!(sight.scenic_score((1, 2)), Some(4));
+        assert_eq!(sight.scenic_score((5, 0)), None);
+        assert_eq!(sight.to_string(), "#####\n###.#\n##.##\n#.#.#\n#####\n");
+    }
+
+    #[test]
+    fn matches_rays() {
+        let lines: Vec<String> = (0..13)
+            .map(|r| {
+                (0..17)
+                    .map(|c| char::from(b'0' + ((r * 7 + c * c * 3) % 10) as u8))
+                    .collect()
+            })
+            .collect();
+        let grid = Grid::from_lines(lines.iter()).unwrap();
+        let sight = Sight::new(&grid);
+
+        for r in 0..13 {
+            for c in 0..17 {
+                let rays = Direction::ALL.map(|d| ray(&grid, (r, c), d));
+                assert_eq!(sight.is_visible((r, c)), Some(rays.iter().any(|(v, _)| *v)));
+                for (d, (_, dist)) in Direction::ALL.into_iter().zip(rays) {
+                    assert_eq!(sight.viewing_distance((r, c), d), Some(dist));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn uneven_rows() {
+        assert!(Grid::from_lines(["123", "12"].iter()).is_err());
+    }
+}
@@ -1,68 +1,27 @@
-use std::collections::HashSet;
-
 use advent_of_code::errors::Result;
 
-use self::line::SimpleLineIter;
-use self::point::Point;
-use self::vector::Vector;
-
-mod line;
-mod point;
-mod vector;
+use self::{motion::Motion, rope::Rope};
 
-pub fn solution_pt1<S: AsRef<str>, L: Iterator<Item = S>>(lines: L) -> Result<usize> {
-    let mut head: Point = (0, 0).into();
-    let mut tail: Point = head.clone();
-    let mut visited = HashSet::new();
-    visited.insert(tail);
+pub mod motion;
+pub mod point;
+pub mod rope;
 
+/// Number of points the tail of a rope of `knots` knots visits
+fn tail_visits<S: AsRef<str>, L: Iterator<Item = S>>(knots: usize, lines: L) -> Result<usize> {
+    let mut rope = Rope::new(knots)?;
     for line in lines.filter(|l| !l.as_ref().trim().is_empty()) {
-        let move_vec: Vector = line.as_ref().parse()?;
-        // println!("Move: {:?}", move_vec);
-        for _ in 0..move_vec.magnitude().round() as usize {
-            head += move_vec.unit();
-            // println!("\tHead: {:?} | Tail: {:?}", head, tail);
-            let diff = head - tail;
-            // println!("\tDiff: {:?}, {}", diff, diff.magnitude());
-            if diff.magnitude() >= 2.0 {
-                tail += (diff / diff.abs()).round();
-                visited.insert(tail);
-                // println!("\t\t Tail: {:?}", tail);
-            }
-        }
+        let motion: Motion = line.as_ref().parse()?;
+        rope.apply(&motion);
     }
+    Ok(rope.tail_visited().len())
+}
 
-    Ok(visited.len())
+pub fn solution_pt1<S: AsRef<str>, L: Iterator<Item = S>>(lines: L) -> Result<usize> {
+    tail_visits(2, lines)
 }
 
 pub fn solution_pt2<S: AsRef<str>, L: Iterator<Item = S>>(lines: L) -> Result<usize> {
-    let mut head: Point = (0, 0).into();
-    let mut tails: Vec<Point> = Vec::from([head; 9]);
-    let mut visited = HashSet::new();
-    visited.insert(head);
-
-    for line in lines.filter(|l| !l.as_ref().trim().is_empty()) {
-        let move_vec: Vector = line.as_ref().parse()?;
-        // println!("Move: {:?}", move_vec);
-        for _ in 0..move_vec.magnitude().round() as usize {
-            head += move_vec.unit();
-            // println!("\tHead: {:?} | Tail: {:?}", head, tails);
-            let mut cur = head;
-            for (i, t) in tails.iter_mut().enumerate() {
-                let diff = cur - *t;
-                if diff.magnitude() >= 2.0 {
-                    *t += (diff / diff.abs()).round();
-                    if i == 8 {
-                        visited.insert(*t);
-                    }
-                    // println!("\t\t TailUpdate: {} -> {:?}", i, t);
-                }
-                cur = *t;
-            }
-        }
-    }
-
-    Ok(visited.len())
+    tail_visits(10, lines)
 }
 
 #[cfg(test)]
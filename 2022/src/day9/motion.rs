@@ -0,0 +1,74 @@
+use std::str::FromStr;
+
+use advent_of_code::errors::Error;
+
+use super::point::Point;
+
+/// A line of the puzzle input: the head steps `count` times in one direction. Besides `U`, `D`,
+/// `L` and `R` the head can move diagonally with `UL`, `UR`, `DL` and `DR`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Motion {
+    step: Point,
+    count: usize,
+}
+
+impl Motion {
+    pub fn new(step: Point, count: usize) -> Self {
+        Motion {
+            step: step.signum(),
+            count,
+        }
+    }
+
+    /// Where a single step moves the head, at most one in each axis
+    pub fn step(&self) -> Point {
+        self.step
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+impl FromStr for Motion {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut splits = s.split_whitespace();
+
+        let dir = splits
+            .next()
+            .ok_or(Error::InvalidParseError("No direction".to_owned()))?;
+        let count = usize::from_str(
+            splits
+                .next()
+                .ok_or(Error::InvalidParseError("No amount".to_owned()))?,
+        )?;
+        let step = dir.chars().try_fold(Point::default(), |step, c| {
+            let axis: Point = match c {
+                'U' => (0, 1).into(),
+                'D' => (0, -1).into(),
+                'L' => (-1, 0).into(),
+                'R' => (1, 0).into(),
+                _ => {
+                    return Err(Error::InvalidParseError(format!(
+                        "Invalid direction {}",
+                        dir
+                    )))
+                }
+            };
+            // only one vertical and one horizontal part, `UU` or `LR` aren't directions
+            if (axis.x != 0 && step.x != 0) || (axis.y != 0 && step.y != 0) {
+                return Err(Error::InvalidParseError(format!(
+                    "Invalid direction {}",
+                    dir
+                )));
+            }
+            Ok(step + axis)
+        })?;
+        if step == Point::default() {
+            return Err(Error::InvalidParseError("No direction".to_owned()));
+        }
+        Ok(Motion { step, count })
+    }
+}
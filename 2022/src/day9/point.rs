@@ -1,16 +1,23 @@
-use std::ops::{AddAssign, Sub};
+use std::ops::{Add, AddAssign, Sub};
 
-use super::{line::LineAlgorithm, vector::Vector};
-
-#[derive(Clone, Copy, Debug, PartialEq, Hash, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Hash, Eq, PartialOrd, Ord)]
 pub struct Point {
     pub x: i64,
     pub y: i64,
 }
 
 impl Point {
-    pub fn iter_line(&self, end: Point) -> LineAlgorithm {
-        LineAlgorithm::new(*self, end)
+    /// A step of at most one in each axis towards where the point points
+    pub fn signum(&self) -> Point {
+        Point {
+            x: self.x.signum(),
+            y: self.y.signum(),
+        }
+    }
+
+    /// Whether the two points are on top of or right next to each other, diagonals included
+    pub fn touches(&self, other: Point) -> bool {
+        (self.x - other.x).abs() <= 1 && (self.y - other.y).abs() <= 1
     }
 }
 
@@ -23,20 +30,31 @@ impl<T: Into<i64>> From<(T, T)> for Point {
     }
 }
 
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Point {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y,
+        }
+    }
+}
+
 impl Sub for Point {
-    type Output = Vector;
+    type Output = Point;
 
     fn sub(self, rhs: Self) -> Self::Output {
-        Vector {
-            x: (self.x - rhs.x) as f64,
-            y: (self.y - rhs.y) as f64,
+        Point {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
         }
     }
 }
 
-impl AddAssign<Vector> for Point {
-    fn add_assign(&mut self, rhs: Vector) {
-        self.x += rhs.x.round() as i64;
-        self.y += rhs.y.round() as i64;
+impl AddAssign for Point {
+    fn add_assign(&mut self, rhs: Self) {
+        self.x += rhs.x;
+        self.y += rhs.y;
     }
 }
@@ -0,0 +1,244 @@
+use std::{collections::HashSet, fmt::Display};
+
+use advent_of_code::errors::{Error, Result};
+
+use super::{motion::Motion, point::Point};
+
+/// A rope of knots where every knot follows the one in front of it, the first knot is the head
+/// and the last one the tail. All knots start on the same point.
+#[derive(Debug, Clone)]
+pub struct Rope {
+    start: Point,
+    knots: Vec<Point>,
+    /// every point each knot has been on
+    visited: Vec<HashSet<Point>>,
+    /// every point the tail moved to in order, starting with the start
+    tail_history: Vec<Point>,
+}
+
+impl Rope {
+    pub fn new(knots: usize) -> Result<Self> {
+        Self::starting_at(knots, Point::default())
+    }
+
+    pub fn starting_at(knots: usize, start: Point) -> Result<Self> {
+        if knots == 0 {
+            return Err(Error::InvalidStruct(
+                "A rope needs at least one knot".to_owned(),
+            ));
+        }
+        Ok(Rope {
+            start,
+            knots: vec![start; knots],
+            visited: vec![HashSet::from([start]); knots],
+            tail_history: vec![start],
+        })
+    }
+
+    pub fn knots(&self) -> &[Point] {
+        &self.knots
+    }
+
+    pub fn head(&self) -> Point {
+        self.knots[0]
+    }
+
+    pub fn tail(&self) -> Point {
+        self.knots[self.knots.len() - 1]
+    }
+
+    /// Points the knot at `index` has been on
+    pub fn visited(&self, index: usize) -> Option<&HashSet<Point>> {
+        self.visited.get(index)
+    }
+
+    pub fn tail_visited(&self) -> &HashSet<Point> {
+        &self.visited[self.knots.len() - 1]
+    }
+
+    /// Every point the tail moved to, in order
+    pub fn tail_history(&self) -> &[Point] {
+        &self.tail_history
+    }
+
+    /// Moves the head a single step, `step` is cut down to at most one in each axis. Each knot
+    /// that no longer touches the one in front steps straight or diagonally towards it.
+    pub fn step(&mut self, step: Point) {
+        self.knots[0] += step.signum();
+        self.visited[0].insert(self.knots[0]);
+        if self.knots.len() == 1 {
+            // the head is the tail as well
+            self.tail_history.push(self.knots[0]);
+        }
+
+        for i in 1..self.knots.len() {
+            let ahead = self.knots[i - 1];
+            if self.knots[i].touches(ahead) {
+                // the knots behind can't move either
+                break;
+            }
+            let towards = (ahead - self.knots[i]).signum();
+            self.knots[i] += towards;
+            self.visited[i].insert(self.knots[i]);
+            if i == self.knots.len() - 1 {
+                self.tail_history.push(self.knots[i]);
+            }
+        }
+    }
+
+    pub fn apply(&mut self, motion: &Motion) {
+        for _ in 0..motion.count() {
+            self.step(motion.step());
+        }
+    }
+
+    /// Smallest and largest corner of every point any knot has been on
+    fn bounds(&self) -> (Point, Point) {
+        self.visited
+            .iter()
+            .flatten()
+            .fold((self.start, self.start), |(min, max), p| {
+                (
+                    (min.x.min(p.x), min.y.min(p.y)).into(),
+                    (max.x.max(p.x), max.y.max(p.y)).into(),
+                )
+            })
+    }
+
+    /// Draws every point the tail has been on as `#` with the start as `s`, like the puzzle does
+    pub fn render_tail_visited(&self) -> String {
+        let visited = self.tail_visited();
+        self.render(|p| {
+            if p == self.start {
+                's'
+            } else if visited.contains(&p) {
+                '#'
+            } else {
+                '.'
+            }
+        })
+    }
+
+    /// Draws the area every knot has been in with up pointing up, `draw` picks the character of
+    /// each point
+    fn render<F: Fn(Point) -> char>(&self, draw: F) -> String {
+        let (min, max) = self.bounds();
+        let mut out = String::new();
+        for y in (min.y..=max.y).rev() {
+            out.extend((min.x..=max.x).map(|x| draw((x, y).into())));
+            out.push('\n');
+        }
+        out
+    }
+
+    fn label(&self, index: usize) -> char {
+        match index {
+            0 => 'H',
+            1 if self.knots.len() == 2 => 'T',
+            i => char::from_digit(i as u32, 36).unwrap_or('#'),
+        }
+    }
+}
+
+/// Draws the knots like the puzzle, `H` for the head, `T` or the knot's number for the rest and
+/// `s` for the start. Knots closer to the head cover the ones behind.
+impl Display for Rope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let drawn = self.render(|p| match self.knots.iter().position(|k| *k == p) {
+            Some(i) => self.label(i),
+            None if p == self.start => 's',
+            None => '.',
+        });
+        write!(f, "{}", drawn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Rope;
+    use crate::day9::motion::Motion;
+
+    fn run(knots: usize, motions: &str) -> Rope {
+        let mut rope = Rope::new(knots).unwrap();
+        for m in motions.lines() {
+            rope.apply(&m.parse().unwrap());
+        }
+        rope
+    }
+
+    #[test]
+    fn short_rope() {
+        let rope = run(2, "R 4\nU 4\nL 3\nD 1\nR 4\nD 1\nL 5\nR 2");
+
+        assert_eq!(rope.tail_visited().len(), 13);
+        assert_eq!(rope.visited(0).map(|v| v.len()), Some(21));
+        // the tail steps back onto a point it has been on once
+        assert_eq!(rope.tail_history().len(), 14);
+        assert_eq!(rope.head(), (2, 2).into());
+        assert_eq!(rope.tail(), (1, 2).into());
+        assert_eq!(
+            rope.to_string(),
+            "\
+......
+......
+.TH...
+......
+s.....
+"
+        );
+        assert_eq!(
+            rope.render_tail_visited(),
+            "\
+..##..
+...##.
+.####.
+....#.
+s###..
+"
+        );
+    }
+
+    #[test]
+    fn long_rope() {
+        let rope = run(10, "R 4\nU 4");
+        assert_eq!(
+            rope.to_string(),
+            "\
+....H
+....1
+..432
+.5...
+6....
+"
+        );
+        assert_eq!(rope.knots()[9], (0, 0).into());
+        assert_eq!(rope.tail_history(), [(0, 0).into()]);
+
+        let rope = run(10, "R 5\nU 8\nL 8\nD 3\nR 17\nD 10\nL 25\nU 20");
+        assert_eq!(rope.tail_visited().len(), 36);
+        assert_eq!(rope.tail_history().len(), 36);
+    }
+
+    #[test]
+    fn single_knot() {
+        let rope = run(1, "R 2\nU 1\nL 2");
+
+        assert_eq!(rope.head(), rope.tail());
+        assert_eq!(
+            rope.tail_history(),
+            [(0, 0), (1, 0), (2, 0), (2, 1), (1, 1), (0, 1)].map(Into::into)
+        );
+        assert_eq!(rope.tail_visited().len(), rope.tail_history().len());
+    }
+
+    #[test]
+    fn diagonal_head() {
+        let rope = run(3, "UR 3\nDL 1");
+
+        assert_eq!(rope.knots(), [(2, 2).into(), (2, 2).into(), (1, 1).into()]);
+        assert_eq!(rope.visited(1).map(|v| v.len()), Some(3));
+        assert!("UU 1".parse::<Motion>().is_err());
+        assert!("X 1".parse::<Motion>().is_err());
+        assert!(Rope::new(0).is_err());
+    }
+}
@@ -1,61 +1,165 @@
+use std::collections::BTreeMap;
+
 use super::instruction::Instruction;
 
+/// Named registers, any register that was never set holds 0
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Registers(BTreeMap<String, i64>);
+
+impl Registers {
+    pub fn get(&self, name: &str) -> i64 {
+        self.0.get(name).copied().unwrap_or_default()
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> &mut i64 {
+        self.0.entry(name.to_owned()).or_default()
+    }
+
+    pub fn set(&mut self, name: &str, value: i64) {
+        *self.get_mut(name) = value;
+    }
+
+    pub fn x(&self) -> i64 {
+        self.get("x")
+    }
+}
+
+/// Gets to look at the registers during every cycle, before the instruction running finishes
+pub trait Observer {
+    /// `cycle` counts from 1
+    fn during(&mut self, cycle: usize, registers: &Registers);
+}
+
+impl<F: FnMut(usize, &Registers)> Observer for F {
+    fn during(&mut self, cycle: usize, registers: &Registers) {
+        self(cycle, registers)
+    }
+}
+
+/// Lets two observers watch the same run
+impl<A: Observer, B: Observer> Observer for (A, B) {
+    fn during(&mut self, cycle: usize, registers: &Registers) {
+        self.0.during(cycle, registers);
+        self.1.during(cycle, registers);
+    }
+}
+
 #[derive(Debug)]
 pub struct Cpu {
+    /// cycles finished so far
     cycle: usize,
-    x: i64,
+    registers: Registers,
 }
 
+/// Starts with `x` at 1
 impl Default for Cpu {
     fn default() -> Self {
-        Cpu { cycle: 0, x: 1 }
+        let mut registers = Registers::default();
+        registers.set("x", 1);
+        Cpu::new(registers)
     }
 }
 
 impl Cpu {
-    pub fn run_signal(&mut self, instruction: Instruction) -> i64 {
-        let mut signal_strength = 0;
-        let cycles = instruction.cycles();
-        // the cycle begins (zero indexed)
-        for _ in 0..cycles {
-            //during cycle
-            if let Some(cyc) = (self.cycle + 1).checked_sub(20) {
-                if cyc % 40 == 0 {
-                    signal_strength = (self.cycle + 1) as i64 * self.x;
-                }
-            }
-            // end of cycle
+    pub fn new(registers: Registers) -> Self {
+        Cpu {
+            cycle: 0,
+            registers,
+        }
+    }
+
+    pub fn cycle(&self) -> usize {
+        self.cycle
+    }
+
+    pub fn registers(&self) -> &Registers {
+        &self.registers
+    }
+
+    /// Runs the instruction to the end calling the observer during each of its cycles
+    pub fn execute<O: Observer + ?Sized>(
+        &mut self,
+        instruction: &dyn Instruction,
+        observer: &mut O,
+    ) {
+        for _ in 0..instruction.cycles() {
             self.cycle += 1;
+            observer.during(self.cycle, &self.registers);
         }
+        instruction.execute(&mut self.registers);
+    }
 
-        match instruction {
-            Instruction::AddX(v) => self.x += v,
-            _ => {}
+    pub fn run<O, I>(&mut self, program: I, observer: &mut O)
+    where
+        O: Observer + ?Sized,
+        I: IntoIterator<Item = Box<dyn Instruction>>,
+    {
+        for instruction in program {
+            self.execute(instruction.as_ref(), observer);
         }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use advent_of_code::errors::Error;
+
+    use super::{Cpu, Registers};
+    use crate::day_10::{
+        instruction::{Instruction, InstructionSet},
+        observer::{Crt, SignalSampler},
+    };
 
-        signal_strength
-    }
-
-    /// Determines if the pixel should be lit up or not
-    pub fn draw_instruction(&mut self, instruction: Instruction, crt: &mut String) {
-        let cycles = instruction.cycles();
-
-        for _ in 0..cycles {
-            let rem = self.cycle % 40;
-            // println!("{} | {}", self.x, rem);
-            if self.x - 1 <= rem as i64 && rem as i64 <= self.x + 1 {
-                crt.push('#');
-            } else {
-                crt.push('.')
-            }
-            if (self.cycle + 1) % 40 == 0 {
-                crt.push('\n');
-            }
-            self.cycle += 1
+    /// Multiplies `x` by a value, slower than adding
+    #[derive(Debug)]
+    struct MulX(i64);
+
+    impl Instruction for MulX {
+        fn cycles(&self) -> usize {
+            3
         }
-        match instruction {
-            Instruction::AddX(v) => self.x += v,
-            _ => {}
+
+        fn execute(&self, registers: &mut Registers) {
+            *registers.get_mut("x") *= self.0;
         }
     }
+
+    #[test]
+    fn new_instruction() {
+        let set = InstructionSet::default().with("mulx", |args| {
+            let v = args
+                .first()
+                .ok_or(Error::InvalidParseError("mulx".to_owned()))?;
+            Ok(Box::new(MulX(v.parse()?)))
+        });
+        let program = ["addx 2", "mulx 4", "noop"]
+            .into_iter()
+            .map(|l| set.decode(l))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        let mut seen = Vec::new();
+        let mut cpu = Cpu::default();
+        cpu.run(program, &mut |cycle, r: &Registers| {
+            seen.push((cycle, r.x()))
+        });
+
+        assert_eq!(cpu.cycle(), 6);
+        assert_eq!(cpu.registers().x(), 12);
+        assert_eq!(seen, vec![(1, 1), (2, 1), (3, 3), (4, 3), (5, 3), (6, 12)]);
+        assert!(set.decode("jmp 3").is_err());
+    }
+
+    #[test]
+    fn combined_observers() {
+        let set = InstructionSet::default();
+        let program = ["noop", "addx 3", "addx -5", "noop"].map(|l| set.decode(l).unwrap());
+
+        let mut observers = (SignalSampler::new(2, 2), Crt::new(3));
+        Cpu::default().run(program, &mut observers);
+
+        // x is 1 during cycle 2, 4 during cycle 4 and -1 during cycle 6
+        assert_eq!(observers.0.total(), 2 + 16 - 6);
+        assert_eq!(observers.1.to_string(), "###\n...");
+    }
 }
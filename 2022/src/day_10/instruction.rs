@@ -1,41 +1,89 @@
-use std::str::FromStr;
+use std::{collections::HashMap, fmt::Debug, str::FromStr};
 
-use advent_of_code::errors::Error;
+use advent_of_code::errors::{Error, Result};
 
-#[derive(Debug)]
-pub enum Instruction {
-    Noop,
-    AddX(i64),
+use super::cpu::Registers;
+
+/// Something the CPU can run, it takes some cycles and then changes the registers
+pub trait Instruction: Debug {
+    /// Number of cycles it takes before the instruction is done
+    fn cycles(&self) -> usize;
+
+    /// Changes the registers at the end of the last cycle
+    fn execute(&self, registers: &mut Registers);
 }
 
-impl Instruction {
-    pub fn cycles(&self) -> usize {
-        match self {
-            Self::Noop => 1,
-            Self::AddX(_) => 2,
-        }
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Noop;
+
+/// Adds a value to the `x` register
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AddX(pub i64);
+
+impl Instruction for Noop {
+    fn cycles(&self) -> usize {
+        1
+    }
+
+    fn execute(&self, _registers: &mut Registers) {}
+}
+
+impl Instruction for AddX {
+    fn cycles(&self) -> usize {
+        2
+    }
+
+    fn execute(&self, registers: &mut Registers) {
+        *registers.get_mut("x") += self.0;
     }
 }
 
-impl FromStr for Instruction {
-    type Err = Error;
+/// Makes an instruction from the arguments after its name
+pub type Decoder = fn(&[&str]) -> Result<Box<dyn Instruction>>;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut splits = s.trim().split_whitespace();
-        let i = splits
+/// The instructions a program can use by name
+pub struct InstructionSet {
+    decoders: HashMap<String, Decoder>,
+}
+
+impl InstructionSet {
+    pub fn empty() -> Self {
+        InstructionSet {
+            decoders: HashMap::new(),
+        }
+    }
+
+    /// Lets programs use `name`, replacing any instruction already called that
+    pub fn with(mut self, name: &str, decoder: Decoder) -> Self {
+        self.decoders.insert(name.to_owned(), decoder);
+        self
+    }
+
+    /// Reads a line like `addx 3`
+    pub fn decode(&self, line: &str) -> Result<Box<dyn Instruction>> {
+        let mut splits = line.split_whitespace();
+        let name = splits
             .next()
             .ok_or(Error::InvalidParseError("Need an instruction".to_owned()))?;
-        let inc = match i {
-            "noop" => Instruction::Noop,
-            "addx" => {
-                let v = splits
-                    .next()
-                    .ok_or(Error::InvalidParseError("No value in addx".to_owned()))?;
-                Instruction::AddX(i64::from_str(v)?)
-            }
-            _ => return Err(Error::InvalidParseError("unknown op".to_owned())),
-        };
+        let args: Vec<&str> = splits.collect();
+        let decoder = self
+            .decoders
+            .get(name)
+            .ok_or_else(|| Error::InvalidParseError(format!("unknown op {}", name)))?;
+        decoder(&args)
+    }
+}
 
-        Ok(inc)
+/// `noop` and `addx`
+impl Default for InstructionSet {
+    fn default() -> Self {
+        InstructionSet::empty()
+            .with("noop", |_| Ok(Box::new(Noop)))
+            .with("addx", |args| {
+                let v = args
+                    .first()
+                    .ok_or(Error::InvalidParseError("No value in addx".to_owned()))?;
+                Ok(Box::new(AddX(i64::from_str(v)?)))
+            })
     }
 }
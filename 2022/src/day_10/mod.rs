@@ -1,38 +1,39 @@
-use advent_of_code::{errors::Result, strings::TrimInPlace};
+use advent_of_code::errors::Result;
 
-use self::{cpu::Cpu, instruction::Instruction};
+use self::{
+    cpu::{Cpu, Observer},
+    instruction::{Instruction, InstructionSet},
+    observer::{Crt, SignalSampler},
+};
 
-mod cpu;
-mod instruction;
+pub mod cpu;
+pub mod instruction;
+pub mod observer;
 
-pub fn solution_pt1<S: AsRef<str>, L: Iterator<Item = S>>(lines: L) -> Result<i64> {
-    let mut cpu: Cpu = Default::default();
-
-    lines
+fn run<S: AsRef<str>, L: Iterator<Item = S>, O: Observer>(
+    lines: L,
+    observer: &mut O,
+) -> Result<()> {
+    let instructions = InstructionSet::default();
+    let program = lines
         .filter(|l| !l.as_ref().trim().is_empty())
-        .map(|line| {
-            let line = line.as_ref().trim();
-            let signal = cpu.run_signal(line.parse()?);
-            // if signal > 0 {
-            //     println!("{:?} -> {}", cpu, signal);
-            // }
+        .map(|line| instructions.decode(line.as_ref()))
+        .collect::<Result<Vec<Box<dyn Instruction>>>>()?;
 
-            Ok(signal)
-        })
-        .sum()
+    Cpu::default().run(program, observer);
+    Ok(())
 }
 
-pub fn solution_pt2<S: AsRef<str>, L: Iterator<Item = S>>(lines: L) -> Result<String> {
-    let mut cpu: Cpu = Default::default();
-    let mut crt_screen = String::new();
-
-    for line in lines.filter(|l| !l.as_ref().trim().is_empty()) {
-        let line = line.as_ref().trim();
-        cpu.draw_instruction(line.parse()?, &mut crt_screen);
-    }
+pub fn solution_pt1<S: AsRef<str>, L: Iterator<Item = S>>(lines: L) -> Result<i64> {
+    let mut sampler = SignalSampler::default();
+    run(lines, &mut sampler)?;
+    Ok(sampler.total())
+}
 
-    crt_screen.trim_in_place();
-    Ok(crt_screen)
+pub fn solution_pt2<S: AsRef<str>, L: Iterator<Item = S>>(lines: L) -> Result<String> {
+    let mut crt = Crt::default();
+    run(lines, &mut crt)?;
+    Ok(crt.to_string())
 }
 
 #[cfg(test)]
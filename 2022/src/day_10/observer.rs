@@ -0,0 +1,90 @@
+use std::fmt::Display;
+
+use super::cpu::{Observer, Registers};
+
+/// Adds up the signal strength, the cycle times `x`, at `first` and then every `every` cycles
+#[derive(Debug, Clone)]
+pub struct SignalSampler {
+    first: usize,
+    every: usize,
+    total: i64,
+}
+
+impl SignalSampler {
+    pub fn new(first: usize, every: usize) -> Self {
+        SignalSampler {
+            first,
+            every,
+            total: 0,
+        }
+    }
+
+    pub fn total(&self) -> i64 {
+        self.total
+    }
+}
+
+/// Cycle 20 and every 40 after it
+impl Default for SignalSampler {
+    fn default() -> Self {
+        SignalSampler::new(20, 40)
+    }
+}
+
+impl Observer for SignalSampler {
+    fn during(&mut self, cycle: usize, registers: &Registers) {
+        let sampled = cycle
+            .checked_sub(self.first)
+            .is_some_and(|since| since % self.every.max(1) == 0);
+        if sampled {
+            self.total += cycle as i64 * registers.x();
+        }
+    }
+}
+
+/// A screen drawing one pixel a cycle, left to right and then the next row. The pixel is lit when
+/// the sprite, 3 pixels wide and centered on `x`, covers it.
+#[derive(Debug, Clone)]
+pub struct Crt {
+    width: usize,
+    rows: Vec<String>,
+}
+
+impl Crt {
+    pub fn new(width: usize) -> Self {
+        Crt {
+            width: width.max(1),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn rows(&self) -> &[String] {
+        &self.rows
+    }
+}
+
+/// 40 pixels wide
+impl Default for Crt {
+    fn default() -> Self {
+        Crt::new(40)
+    }
+}
+
+impl Observer for Crt {
+    fn during(&mut self, cycle: usize, registers: &Registers) {
+        let column = (cycle - 1) % self.width;
+        if column == 0 {
+            self.rows.push(String::with_capacity(self.width));
+        }
+        let lit = (registers.x() - column as i64).abs() <= 1;
+        if let Some(row) = self.rows.last_mut() {
+            row.push(if lit { '#' } else { '.' });
+        }
+    }
+}
+
+impl Display for Crt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.rows.join("\n"))
+    }
+}
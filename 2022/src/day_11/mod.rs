@@ -1,6 +1,10 @@
 use std::collections::{BTreeMap, HashMap};
 
-use advent_of_code::errors::{Error, Result};
+use advent_of_code::{
+    errors::{Error, Result},
+    number::lcm_all,
+    top_k::TopKExt,
+};
 
 use self::monkey::Monkey;
 
@@ -36,13 +40,7 @@ pub fn solution_pt1<S: AsRef<str>, L: Iterator<Item = S>>(lines: L) -> Result<us
         }
     }
 
-    let mut active: Vec<usize> = visited.into_iter().map(|(_, v)| v).collect();
-
-    active.sort_unstable();
-    active.reverse();
-    let active = active[0] * active[1];
-
-    Ok(active)
+    monkey_business(visited.into_values())
 }
 
 pub fn solution_pt2<S: AsRef<str>, L: Iterator<Item = S>>(lines: L) -> Result<usize> {
@@ -55,11 +53,15 @@ pub fn solution_pt2<S: AsRef<str>, L: Iterator<Item = S>>(lines: L) -> Result<us
         monkies.insert(monkey.index, monkey);
     }
 
-    let mut common_denom = 1;
-
-    for (_, m) in monkies.iter() {
-        common_denom *= m.test.divisor as i64;
-    }
+    // every test still passes the same way when worry levels are kept modulo this
+    let common_denom = lcm_all(monkies.values().map(|m| m.test.divisor))
+        .ok_or("Divisors have no common multiple that fits")?;
+    let common_denom = i64::try_from(common_denom).map_err(|_| {
+        Error::InvalidStruct(format!(
+            "Common multiple of the divisors {} is too large for worry levels",
+            common_denom
+        ))
+    })?;
 
     // run rounds
     for _ in 0..10_000 {
@@ -80,13 +82,15 @@ pub fn solution_pt2<S: AsRef<str>, L: Iterator<Item = S>>(lines: L) -> Result<us
         }
     }
 
-    let mut active: Vec<usize> = visited.into_iter().map(|(_, v)| v).collect();
-
-    active.sort_unstable();
-    active.reverse();
-    let active = active[0] * active[1];
+    monkey_business(visited.into_values())
+}
 
-    Ok(active)
+/// Product of the two largest activity counts
+fn monkey_business<I: Iterator<Item = usize>>(active: I) -> Result<usize> {
+    match active.top_k(2)[..] {
+        [first, second] => Ok(first * second),
+        _ => Err(Error::InvalidStruct("Need at least two monkeys".to_owned())),
+    }
 }
 
 #[cfg(test)]
@@ -1,72 +1,35 @@
-use advent_of_code::errors::Error;
+use advent_of_code::{
+    errors::Error,
+    expr::{Expr, Op},
+    number::{mod_add, mod_mul},
+};
 use std::hash::Hash;
 use std::{borrow::Borrow, collections::HashMap, str::FromStr};
 
-use super::test::Test;
-
+/// How a monkey changes the worry level of an item, the expression on the right of `new =`
 #[derive(Debug)]
-pub enum Token {
-    Var(String),
-    Value(i64),
-}
-
-impl Token {
-    pub fn value<K: Borrow<str> + Hash + Eq>(&self, value_lookup: &HashMap<K, i64>) -> Option<i64> {
-        match self {
-            Self::Value(k) => Some(*k),
-            Self::Var(s) => value_lookup.get(&s).copied(),
-        }
-    }
-}
-
-impl FromStr for Token {
-    type Err = Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let s = s.trim();
-        if let Ok(v) = s.parse() {
-            return Ok(Self::Value(v));
-        }
-        if !s.chars().any(|c| !c.is_alphanumeric()) {
-            return Ok(Self::Var(s.to_owned()));
-        }
-        Err(Error::InvalidParseError("Not a valid token".to_owned()))
-    }
-}
-
-#[derive(Debug)]
-pub enum Operation {
-    Multiply(Token, Token),
-    Add(Token, Token),
-}
+pub struct Operation(Expr);
 
 impl Operation {
     pub fn apply<K: Borrow<str> + Hash + Eq>(&self, variables: &HashMap<K, i64>) -> Option<i64> {
-        match self {
-            Self::Multiply(l, r) => Some(l.value(variables)? * r.value(variables)?),
-            Self::Add(l, r) => Some(l.value(variables)? + r.value(variables)?),
-        }
+        self.0.eval(variables).ok()
     }
 
+    /// Same as [`Operation::apply`] but keeps everything modulo `m`, only `+ - *` are supported
     pub fn apply_mod<K: Borrow<str> + Hash + Eq>(
         &self,
         m: i64,
         variables: &HashMap<K, i64>,
     ) -> Option<i64> {
-        match self {
-            Self::Multiply(l, r) => {
-                let l = l.value(variables)? % m;
-                let r = r.value(variables)? % m;
-
-                Some((l * r) % m)
-            }
-            Self::Add(l, r) => {
-                let l = l.value(variables)? % m;
-                let r = r.value(variables)? % m;
-
-                Some((l + r) % m)
-            }
-        }
+        let m = m as u64;
+        let reduce = |n: i64| n.rem_euclid(m as i64) as u64;
+        let out = self.0.fold(variables, &reduce, &|op, l, r| match op {
+            Op::Add => Ok(mod_add(l, r, m)),
+            Op::Sub => Ok(mod_add(l, m - r, m)),
+            Op::Mul => Ok(mod_mul(l, r, m)),
+            Op::Div => Err("Can't divide worry levels kept modulo".into()),
+        });
+        out.ok().map(|n| n as i64)
     }
 }
 
@@ -74,17 +37,6 @@ impl FromStr for Operation {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let s = s.trim();
-        //TODO: abstract the operator
-        let mult_split = s.split_once('*');
-        if let Some((first, second)) = mult_split {
-            return Ok(Operation::Multiply(first.parse()?, second.parse()?));
-        }
-        let add_split = s.split_once('+');
-        if let Some((first, second)) = add_split {
-            return Ok(Operation::Add(first.parse()?, second.parse()?));
-        }
-
-        Err(Error::InvalidParseError("Invalid Operation".to_owned()))
+        Ok(Operation(s.parse()?))
     }
 }
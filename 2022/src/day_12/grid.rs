@@ -1,13 +1,13 @@
-use std::{
-    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
-    fmt::Display,
-};
+use std::{collections::HashSet, fmt::Display};
 
 use super::{
     cell::HeightCell,
     moves::{Direction, Pos},
 };
-use advent_of_code::errors::{Error, Result};
+use advent_of_code::{
+    errors::{Error, Result},
+    search::{bfs, dijkstra},
+};
 
 pub struct HeightMap {
     // top left is (0, 0) and bottom right is (usize, usize)
@@ -118,92 +118,72 @@ impl HeightMap {
         })
     }
 
-    pub fn shortest_path(&self, elevation_diff: u32) -> Vec<Pos> {
-        let mut heap = BinaryHeap::new();
-        let mut distances = HashMap::new();
-
-        heap.push(State {
-            cost: 0,
-            pos: self.start_pos,
-        });
-        distances.insert(self.start_pos, 0);
-
-        let mut visited = HashSet::new();
-        let mut edges = HashMap::new();
-        while let Some(State { cost, pos }) = heap.pop() {
-            let dist = distances[&pos];
-            if pos == self.end_pos {
-                break;
-            }
-            if cost > dist {
-                continue;
-            }
-            // println!(
-            //     "{} = {:?}, Cost: {}, Dist: {}",
-            //     pos, self.grid[pos.r][pos.c], cost, dist
-            // );
-            let height = self.grid[pos.r][pos.c].elevation();
-            for neighbor in self.get_neighbors(pos).filter(|v| !visited.contains(v)) {
-                let neighbor_height = self.grid[neighbor.r][neighbor.c].elevation();
-                let edge_cost = neighbor_height.checked_sub(height).unwrap_or(0);
-                let mut thingy = elevation_diff;
-                if neighbor_height == 0 {
-                    thingy = 0;
-                }
-                let next = State {
-                    cost: cost + edge_cost + thingy,
-                    pos: neighbor,
-                };
-                if edge_cost <= 1
-                    && next.cost < distances.get(&neighbor).copied().unwrap_or(u32::MAX)
-                {
-                    // println!(
-                    //     "\tAdding Neighbor: {} = {:?}, Cost: {}, Dist: {}",
-                    //     neighbor,
-                    //     self.grid[neighbor.r][neighbor.c],
-                    //     cost + new_distance,
-                    //     new_distance
-                    // );
-                    // cost is added because that is the length of each edge added. Distance is
-                    // just the edge distance between two points
-                    distances.insert(neighbor, next.cost);
-                    edges.insert(neighbor, pos);
-                    heap.push(next);
-                }
-            }
-            visited.insert(pos);
-        }
+    /// Neighbors that can be stepped onto from `pos`, at most one level higher
+    fn climbable(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        let height = self.grid[pos.r][pos.c].elevation();
+        self.get_neighbors(pos)
+            .filter(move |n| self.grid[n.r][n.c].elevation() <= height + 1)
+    }
 
-        // println!("{:?}", heap);
-        // for r in 0..self.dims.0 {
-        //     for c in 0..self.dims.1 {
-        //         print!(
-        //             "{}",
-        //             distances
-        //                 .get(&(r, c).into())
-        //                 .and_then(|v| char::from_u32(*v + 'a' as u32))
-        //                 .unwrap_or('#'),
-        //         );
-        //     }
-        //     println!();
-        // }
-
-        let mut path = Vec::new();
-        path.push(self.end_pos);
-        if edges.contains_key(&self.end_pos) {
-            let mut cur = self.end_pos;
-            while let Some(pos) = edges.get(&cur) {
-                path.push(*pos);
-                cur = *pos;
-            }
-        }
-        path.reverse();
+    /// Neighbors that `pos` can be reached from, at most one level lower. This is the climb rule
+    /// walked backwards
+    fn descendable(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        let height = self.grid[pos.r][pos.c].elevation();
+        self.get_neighbors(pos)
+            .filter(move |n| height <= self.grid[n.r][n.c].elevation() + 1)
+    }
+
+    /// Every cell at the lowest elevation, the start included
+    pub fn lowest_cells(&self) -> impl Iterator<Item = Pos> + '_ {
+        self.grid.iter().enumerate().flat_map(|(r, row)| {
+            row.iter()
+                .enumerate()
+                .filter(|(_, cell)| cell.elevation() == 0)
+                .map(move |(c, _)| (r, c).into())
+        })
+    }
 
-        // for (i, pos) in path.iter().enumerate() {
-        //     println!("{} : {}", i, pos);
-        // }
+    /// Shortest path from the start to the end, both inclusive. `None` if the end can't be reached
+    pub fn shortest_path(&self) -> Option<Vec<Pos>> {
+        self.shortest_path_from(self.start_pos)
+    }
+
+    /// Shortest path from `start` to the end, both inclusive. `None` if the end can't be reached
+    pub fn shortest_path_from(&self, start: Pos) -> Option<Vec<Pos>> {
+        dijkstra(
+            start,
+            |pos| self.climbable(*pos).map(|n| (n, 1u32)),
+            |pos| *pos == self.end_pos,
+        )
+        .map(|(_, path)| path)
+    }
+
+    /// Shortest path to the end starting from whichever lowest cell is closest, all of them are
+    /// searched at once. The first position of the path is the chosen start cell
+    pub fn shortest_path_from_lowest(&self) -> Option<Vec<Pos>> {
+        bfs(
+            self.lowest_cells(),
+            |pos| self.climbable(*pos),
+            |pos| *pos == self.end_pos,
+        )
+    }
+
+    /// Same as [`Self::shortest_path_from_lowest`] but searches backwards from the end until the
+    /// first lowest cell is found
+    pub fn shortest_path_to_lowest(&self) -> Option<Vec<Pos>> {
+        let mut path = bfs(
+            [self.end_pos],
+            |pos| self.descendable(*pos),
+            |pos| self.grid[pos.r][pos.c].elevation() == 0,
+        )?;
+        path.reverse();
+        Some(path)
+    }
 
-        path
+    /// The lowest cell closest to the end and the number of steps from it to the end
+    pub fn closest_lowest(&self) -> Option<(Pos, usize)> {
+        let path = self.shortest_path_to_lowest()?;
+        Some((*path.first()?, path.len() - 1))
     }
 }
 
@@ -218,21 +198,3 @@ impl Display for HeightMap {
         Ok(())
     }
 }
-
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
-struct State {
-    cost: u32,
-    pos: Pos,
-}
-
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        other.cost.cmp(&self.cost)
-    }
-}
-
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
-}
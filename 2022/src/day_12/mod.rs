@@ -11,7 +11,7 @@ pub fn solution_pt1<S: AsRef<str>, L: Iterator<Item = S>>(mut lines: L) -> Resul
     let height_map = HeightMap::from_lines(&mut lines)?;
     println!("{}", height_map);
 
-    let path = height_map.shortest_path(1);
+    let path = height_map.shortest_path().ok_or("No path to the end")?;
     height_map.print_path(&path);
 
     Ok(path.len() - 1)
@@ -21,31 +21,14 @@ pub fn solution_pt2<S: AsRef<str>, L: Iterator<Item = S>>(mut lines: L) -> Resul
     let height_map = HeightMap::from_lines(&mut lines)?;
     println!("{}", height_map);
 
-    let start_height = height_map.start().elevation();
-    let mut path = height_map.shortest_path(1);
-    let i = path
-        .iter()
-        .enumerate()
-        .find_map(|(i, pos)| {
-            let elevation = height_map.get_cell(*pos).unwrap().elevation();
-            if elevation == start_height {
-                None
-            } else {
-                Some(i)
-            }
-        })
-        .unwrap()
-        - 1;
-
-    path.drain(0..i);
-    height_map.print_path(&path);
+    let (_, steps) = height_map.closest_lowest().ok_or("No path to the end")?;
 
-    Ok(path.len() - 1)
+    Ok(steps)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{solution_pt1, solution_pt2};
+    use super::{grid::HeightMap, solution_pt1, solution_pt2};
 
     const PAGE_EXAMPLE: &str = r#"
 Sabqponm
@@ -70,4 +53,17 @@ abdefghi
 
         assert_eq!(actual, 29)
     }
+
+    #[test]
+    fn lowest_start_both_directions() {
+        let height_map = HeightMap::from_lines(&mut PAGE_EXAMPLE.lines()).unwrap();
+        let forward = height_map.shortest_path_from_lowest().unwrap();
+        let backward = height_map.shortest_path_to_lowest().unwrap();
+
+        assert_eq!(forward.len() - 1, 29);
+        assert_eq!(backward.len() - 1, 29);
+        assert_eq!(forward[0], (4, 0).into());
+        assert_eq!(backward[0], (4, 0).into());
+        assert_eq!(height_map.closest_lowest(), Some(((4, 0).into(), 29)));
+    }
 }
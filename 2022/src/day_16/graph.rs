@@ -1,3 +1,4 @@
+use advent_of_code::topo::{topological_sort, CycleError};
 use core::fmt::Debug;
 use core::hash::Hash;
 use std::{
@@ -6,6 +7,10 @@ use std::{
     rc::Rc,
 };
 
+/// Shortest distances between pairs of vertices, `table[from][to]`. Pairs without a path between
+/// them are left out.
+pub type DistanceTable<V> = HashMap<Rc<V>, HashMap<Rc<V>, usize>>;
+
 pub struct Graph<V, E> {
     edges: HashMap<Rc<V>, HashMap<Rc<V>, Rc<E>>>,
 }
@@ -63,22 +68,262 @@ impl<V: Hash + Eq, E> Graph<V, E> {
     pub fn get_edge(&self, from: &Rc<V>, to: &Rc<V>) -> Option<&Rc<E>> {
         self.edges.get(from).and_then(|e| e.get(to))
     }
+
+    pub fn vertices(&self) -> impl Iterator<Item = &Rc<V>> {
+        self.edges.keys()
+    }
+
+    /// Number of edges coming into every vertex
+    pub fn in_degrees(&self) -> HashMap<Rc<V>, usize> {
+        let mut degrees: HashMap<Rc<V>, usize> = self.vertices().map(|v| (v.clone(), 0)).collect();
+        for to in self.edges.values().flat_map(|e| e.keys()) {
+            *degrees.entry(to.clone()).or_default() += 1;
+        }
+        degrees
+    }
+
+    /// Orders the vertices so every edge goes from an earlier vertex to a later one, ties are
+    /// broken by the smallest vertex. Fails with the vertices of a cycle if there is one.
+    pub fn topological_order(&self) -> std::result::Result<Vec<Rc<V>>, CycleError<Rc<V>>>
+    where
+        V: Ord,
+    {
+        topological_sort(
+            self.vertices().cloned(),
+            self.edges
+                .iter()
+                .flat_map(|(from, e)| e.keys().map(|to| (from.clone(), to.clone()))),
+        )
+    }
+
+    /// Distances between every pair of vertices using the Floyd-Warshall algorithm, where
+    /// `weight` gives the length of an edge.
+    pub fn floyd_warshall<F: Fn(&E) -> usize>(&self, weight: F) -> DistanceTable<V> {
+        let vertices: Vec<&Rc<V>> = self.vertices().collect();
+        let index: HashMap<&Rc<V>, usize> =
+            vertices.iter().enumerate().map(|(i, v)| (*v, i)).collect();
+
+        let n = vertices.len();
+        let mut dist = vec![vec![None::<usize>; n]; n];
+        for (i, from) in vertices.iter().enumerate() {
+            dist[i][i] = Some(0);
+            for (to, e) in self.edges[*from].iter() {
+                // tunnels to vertices that were never added have nowhere to go
+                if let Some(&j) = index.get(to) {
+                    let w = weight(e);
+                    dist[i][j] = Some(dist[i][j].map_or(w, |d| d.min(w)));
+                }
+            }
+        }
+
+        for k in 0..n {
+            let through_k = dist[k].clone();
+            for row in dist.iter_mut() {
+                let Some(ik) = row[k] else {
+                    continue;
+                };
+                for (d, kj) in row.iter_mut().zip(through_k.iter()) {
+                    if let Some(kj) = kj {
+                        let through = ik + kj;
+                        if d.is_none_or(|d| through < d) {
+                            *d = Some(through);
+                        }
+                    }
+                }
+            }
+        }
+
+        vertices
+            .iter()
+            .zip(dist)
+            .map(|(from, row)| {
+                let row = vertices
+                    .iter()
+                    .zip(row)
+                    .filter_map(|(to, d)| Some(((*to).clone(), d?)))
+                    .collect();
+                ((*from).clone(), row)
+            })
+            .collect()
+    }
+
+    /// Distances between every pair of vertices counting each edge as one step, found with a
+    /// breadth first search from every vertex.
+    pub fn all_pairs_bfs(&self) -> DistanceTable<V> {
+        self.vertices()
+            .map(|from| {
+                let row = self
+                    .bfs(from)
+                    .map(|visit| (visit.vertex, visit.depth))
+                    .collect();
+                (from.clone(), row)
+            })
+            .collect()
+    }
+
+    /// Builds a graph of only the vertices that satisfy `keep`, connected directly to each other
+    /// by edges weighted with their distance in `distances`.
+    pub fn contract<F: Fn(&V) -> bool>(
+        &self,
+        distances: &DistanceTable<V>,
+        keep: F,
+    ) -> Graph<V, usize> {
+        let mut contracted = Graph::new();
+        for from in self.vertices().filter(|v| keep(v)) {
+            let edges = distances
+                .get(from)
+                .into_iter()
+                .flatten()
+                .filter(|(to, _)| keep(to) && !from.eq(to))
+                .map(|(to, d)| (to.clone(), Rc::new(*d)))
+                .collect();
+            contracted.edges.insert(from.clone(), edges);
+        }
+        contracted
+    }
+}
+
+/// A single step of a [`SearchIter`] traversal.
+#[derive(Debug)]
+pub struct Visit<V> {
+    pub vertex: Rc<V>,
+    /// number of edges between the source and this vertex along the search tree
+    pub depth: usize,
+    /// the vertex this one was reached from, `None` for the source
+    pub predecessor: Option<Rc<V>>,
 }
 
+/// Breadth or depth first traversal of a [`Graph`] starting at a source vertex.
+///
+/// Each vertex is yielded at most once. When a sink is set the iteration stops right after the
+/// sink has been yielded.
 pub struct SearchIter<'a, V, E> {
     graph: &'a Graph<V, E>,
     source: Rc<V>,
-    sink: Rc<V>,
+    sink: Option<Rc<V>>,
     visited: HashSet<Rc<V>>,
-    queue: VecDeque<Rc<V>>,
+    queue: VecDeque<Visit<V>>,
+    predecessors: HashMap<Rc<V>, Rc<V>>,
     is_bfs: bool,
+    done: bool,
+}
+
+impl<V: Hash + Eq, E> Graph<V, E> {
+    /// Breadth first traversal from `source`, vertices come out in order of increasing depth
+    pub fn bfs(&self, source: &Rc<V>) -> SearchIter<'_, V, E> {
+        SearchIter::new(self, source, true)
+    }
+
+    /// Depth first traversal from `source`
+    pub fn dfs(&self, source: &Rc<V>) -> SearchIter<'_, V, E> {
+        SearchIter::new(self, source, false)
+    }
+
+    /// Finds the path with the fewest edges between `from` and `to` (both inclusive)
+    pub fn path(&self, from: &Rc<V>, to: &Rc<V>) -> Option<Vec<Rc<V>>> {
+        let mut search = self.bfs(from).with_sink(to);
+        search.by_ref().for_each(drop);
+        search.path_to(to)
+    }
+}
+
+impl<'a, V: Hash + Eq, E> SearchIter<'a, V, E> {
+    fn new(graph: &'a Graph<V, E>, source: &Rc<V>, is_bfs: bool) -> Self {
+        let mut visited = HashSet::new();
+        if is_bfs {
+            visited.insert(source.clone());
+        }
+        SearchIter {
+            graph,
+            source: source.clone(),
+            sink: None,
+            visited,
+            queue: VecDeque::from([Visit {
+                vertex: source.clone(),
+                depth: 0,
+                predecessor: None,
+            }]),
+            predecessors: HashMap::new(),
+            is_bfs,
+            done: false,
+        }
+    }
+
+    /// Stops the search once `sink` has been reached
+    pub fn with_sink(mut self, sink: &Rc<V>) -> Self {
+        self.sink = Some(sink.clone());
+        self
+    }
+
+    pub fn source(&self) -> &Rc<V> {
+        &self.source
+    }
+
+    /// Rebuilds the path from the source to `v` through the predecessors found so far.
+    ///
+    /// Returns `None` if `v` hasn't been yielded yet.
+    pub fn path_to(&self, v: &Rc<V>) -> Option<Vec<Rc<V>>> {
+        if !self.source.eq(v) && !self.predecessors.contains_key(v) {
+            return None;
+        }
+        let mut path = vec![v.clone()];
+        let mut cur = v;
+        while let Some(prev) = self.predecessors.get(cur) {
+            path.push(prev.clone());
+            cur = prev;
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    fn next_unvisited(&mut self) -> Option<Visit<V>> {
+        if self.is_bfs {
+            // bfs marks vertices as they are queued so the front is always new
+            return self.queue.pop_front();
+        }
+        while let Some(visit) = self.queue.pop_back() {
+            if self.visited.insert(visit.vertex.clone()) {
+                return Some(visit);
+            }
+        }
+        None
+    }
 }
 
 impl<'a, V: Hash + Eq, E> Iterator for SearchIter<'a, V, E> {
-    type Item = Rc<V>;
+    type Item = Visit<V>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        None
+        if self.done {
+            return None;
+        }
+        let visit = self.next_unvisited()?;
+        if let Some(pred) = visit.predecessor.as_ref() {
+            self.predecessors.insert(visit.vertex.clone(), pred.clone());
+        }
+
+        if self.sink.as_ref().is_some_and(|s| s.eq(&visit.vertex)) {
+            self.done = true;
+            return Some(visit);
+        }
+
+        if let Some(edges) = self.graph.edges(&visit.vertex) {
+            for to in edges.keys() {
+                if self.visited.contains(to) {
+                    continue;
+                }
+                if self.is_bfs {
+                    self.visited.insert(to.clone());
+                }
+                self.queue.push_back(Visit {
+                    vertex: to.clone(),
+                    depth: visit.depth + 1,
+                    predecessor: Some(visit.vertex.clone()),
+                });
+            }
+        }
+
+        Some(visit)
     }
 }
 
@@ -105,3 +350,136 @@ impl<V: Debug, E: Debug> Debug for Graph<V, E> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::{DistanceTable, Graph};
+
+    // 1 - 2 - 3 - 4
+    //      \     /
+    //       5 - 6   7
+    fn sample() -> (Graph<usize, ()>, Vec<Rc<usize>>) {
+        let mut g = Graph::new();
+        let v: Vec<Rc<usize>> = (0..=7).map(|i| g.add_vertex(i)).collect();
+        for (a, b) in [(1, 2), (2, 3), (3, 4), (2, 5), (5, 6), (6, 4)] {
+            g.add_edge(&v[a], &v[b], ());
+            g.add_edge(&v[b], &v[a], ());
+        }
+        (g, v)
+    }
+
+    #[test]
+    fn bfs_depths() {
+        let (g, v) = sample();
+        let depths: Vec<(usize, usize)> = g.bfs(&v[1]).map(|s| (*s.vertex, s.depth)).collect();
+
+        assert_eq!(depths.len(), 6);
+        assert_eq!(depths[0], (1, 0));
+        assert!(depths.windows(2).all(|w| w[0].1 <= w[1].1));
+        assert!(depths.contains(&(4, 3)));
+        assert!(depths.contains(&(6, 3)));
+        assert!(!depths.iter().any(|(v, _)| *v == 7));
+    }
+
+    #[test]
+    fn dfs_visits_once() {
+        let (g, v) = sample();
+        let mut visited: Vec<usize> = g.dfs(&v[1]).map(|s| *s.vertex).collect();
+        visited.sort();
+
+        assert_eq!(visited, vec![1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn dfs_predecessors_are_adjacent() {
+        let (g, v) = sample();
+        for visit in g.dfs(&v[3]) {
+            if let Some(pred) = visit.predecessor {
+                assert!(g.get_edge(&pred, &visit.vertex).is_some());
+            }
+        }
+    }
+
+    #[test]
+    fn stops_at_sink() {
+        let (g, v) = sample();
+        let last = g.bfs(&v[1]).with_sink(&v[3]).last().unwrap();
+
+        assert_eq!(*last.vertex, 3);
+        assert_eq!(last.depth, 2);
+    }
+
+    #[test]
+    fn shortest_path() {
+        let (g, v) = sample();
+        let path: Vec<usize> = g.path(&v[1], &v[4]).unwrap().iter().map(|v| **v).collect();
+
+        assert_eq!(path, vec![1, 2, 3, 4]);
+        assert!(g.path(&v[1], &v[7]).is_none());
+        assert_eq!(g.path(&v[1], &v[1]).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn all_pairs_agree() {
+        let (g, v) = sample();
+        let bfs = g.all_pairs_bfs();
+        let fw = g.floyd_warshall(|_| 1);
+
+        assert_eq!(bfs, fw);
+        assert_eq!(fw[&v[1]][&v[4]], 3);
+        assert_eq!(fw[&v[5]][&v[3]], 2);
+        assert!(!fw[&v[1]].contains_key(&v[7]));
+        assert_eq!(fw[&v[7]].len(), 1);
+    }
+
+    #[test]
+    fn weighted_floyd_warshall() {
+        let mut g = Graph::new();
+        let v: Vec<Rc<usize>> = (0..3).map(|i| g.add_vertex(i)).collect();
+        g.add_edge(&v[0], &v[1], 1);
+        g.add_edge(&v[1], &v[2], 1);
+        g.add_edge(&v[0], &v[2], 5);
+        let fw = g.floyd_warshall(|e| *e);
+
+        assert_eq!(fw[&v[0]][&v[2]], 2);
+        assert!(!fw[&v[2]].contains_key(&v[0]));
+    }
+
+    #[test]
+    fn contraction() {
+        let (g, v) = sample();
+        let distances: DistanceTable<usize> = g.all_pairs_bfs();
+        let c = g.contract(&distances, |v| [1, 4, 6].contains(v));
+
+        assert_eq!(c.vertices().count(), 3);
+        assert_eq!(**c.get_edge(&v[1], &v[4]).unwrap(), 3);
+        assert_eq!(**c.get_edge(&v[4], &v[6]).unwrap(), 1);
+        assert!(c.get_edge(&v[1], &v[1]).is_none());
+        assert_eq!(c.edges(&v[6]).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn ordering() {
+        let mut g = Graph::new();
+        let v: Vec<Rc<char>> = "abcd".chars().map(|c| g.add_vertex(c)).collect();
+        g.add_edge(&v[3], &v[1], ());
+        g.add_edge(&v[1], &v[0], ());
+        g.add_edge(&v[2], &v[0], ());
+
+        assert_eq!(g.in_degrees()[&v[0]], 2);
+        let order: Vec<char> = g.topological_order().unwrap().iter().map(|v| **v).collect();
+        assert_eq!(order, vec!['c', 'd', 'b', 'a']);
+
+        g.add_edge(&v[0], &v[3], ());
+        let cycle: Vec<char> = g
+            .topological_order()
+            .unwrap_err()
+            .0
+            .iter()
+            .map(|v| **v)
+            .collect();
+        assert_eq!(cycle, vec!['a', 'd', 'b']);
+    }
+}
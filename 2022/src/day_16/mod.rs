@@ -1,9 +1,10 @@
-use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::collections::HashMap;
 
 use advent_of_code::errors::{Error, Result};
 
-use self::graph::Graph;
+use self::{graph::Graph, network::ValveNetwork};
 mod graph;
+mod network;
 mod valve;
 
 fn parse_valve<S: AsRef<str>>(line: S) -> Result<(String, usize, Vec<String>)> {
@@ -41,12 +42,14 @@ fn parse_valve<S: AsRef<str>>(line: S) -> Result<(String, usize, Vec<String>)> {
     Ok((name, rate, tunnels))
 }
 
-pub fn solution_pt1<S: AsRef<str>, L: Iterator<Item = S>>(lines: L) -> Result<usize> {
+fn parse_network<S: AsRef<str>, L: Iterator<Item = S>>(lines: L) -> Result<ValveNetwork> {
     // The vertices are the string segments and the edge is the flow rate (capacity)
     let mut g: Graph<String, usize> = Graph::new();
+    let mut rates = HashMap::new();
 
     for l in lines.filter(|l| !l.as_ref().trim().is_empty()) {
         let (name, flow, tunnels) = parse_valve(l)?;
+        rates.insert(name.clone(), flow);
         let v = g.add_vertex(name);
         for t in tunnels {
             let t = g.add_vertex(t);
@@ -54,34 +57,23 @@ pub fn solution_pt1<S: AsRef<str>, L: Iterator<Item = S>>(lines: L) -> Result<us
         }
     }
 
-    println!("{:?}", g);
-
     let s = g.vertex("AA".to_string()).ok_or("No start valve")?;
+    ValveNetwork::new(&g, &rates, &s)
+}
 
-    let mut queue = VecDeque::new();
-    let mut visited = BTreeMap::new();
-    let mut paths = BTreeMap::new();
-
-    queue.push_front(s.clone());
-
-    while let Some(cur) = queue.pop_back() {
-        if let Some(edges) = g.edges(&cur) {
-            for (to, e) in edges.iter() {
-                if !visited.contains_key(to) && !to.eq(&s) {
-                    visited.insert(to.clone(), e.clone());
-                    queue.push_front(to.clone());
-                    paths.insert(to.clone(), s.clone());
-                }
-            }
-        }
-    }
+pub fn solution_pt1<S: AsRef<str>, L: Iterator<Item = S>>(lines: L) -> Result<usize> {
+    let network = parse_network(lines)?;
+    Ok(network.max_release(30))
+}
 
-    todo!()
+pub fn solution_pt2<S: AsRef<str>, L: Iterator<Item = S>>(lines: L) -> Result<usize> {
+    let network = parse_network(lines)?;
+    Ok(network.max_release_with_helper(26))
 }
 
 #[cfg(test)]
 mod tests {
-    use super::solution_pt1;
+    use super::{parse_network, solution_pt1, solution_pt2};
 
     const PAGE_EXAMPLE: &str = r#"
 Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
@@ -100,4 +92,31 @@ Valve JJ has flow rate=21; tunnel leads to valve II
         let actual = solution_pt1(PAGE_EXAMPLE.lines()).unwrap();
         assert_eq!(actual, 1651)
     }
+
+    #[test]
+    fn page_example_2() {
+        let actual = solution_pt2(PAGE_EXAMPLE.lines()).unwrap();
+        assert_eq!(actual, 1707)
+    }
+
+    /// A corridor of 40 valves worth opening, far more sets than could be listed one by one
+    #[test]
+    fn many_valves() {
+        let name = |i: usize| format!("V{}", i);
+        let mut lines = vec!["Valve AA has flow rate=0; tunnel leads to valve V0".to_owned()];
+        for i in 0..40 {
+            let prev = if i == 0 { "AA".to_owned() } else { name(i - 1) };
+            let next = if i == 39 { prev.clone() } else { name(i + 1) };
+            lines.push(format!(
+                "Valve {} has flow rate=1; tunnels lead to valves {}, {}",
+                name(i),
+                prev,
+                next
+            ));
+        }
+
+        let network = parse_network(lines.iter()).unwrap();
+        // one opens V0, V1 and V2 for 6 + 4 + 2 and the other V3 and V4 for 3 + 1
+        assert_eq!(network.max_release_with_helper(8), 16);
+    }
 }
@@ -0,0 +1,150 @@
+use std::{cmp::Reverse, collections::HashMap, rc::Rc};
+
+use advent_of_code::{
+    errors::{Error, Result},
+    memo::Memo,
+};
+
+use super::graph::Graph;
+
+/// The valves worth opening and how long it takes to walk between them, every other valve is
+/// contracted away.
+pub struct ValveNetwork {
+    /// flow rate of every valve with a non zero rate, valve `i` is bit `i` of an opened mask
+    rates: Vec<usize>,
+    /// minutes to walk from one valve to another, the start valve is the last row
+    distances: Vec<Vec<Option<usize>>>,
+}
+
+impl ValveNetwork {
+    pub fn new(
+        g: &Graph<String, usize>,
+        rates: &HashMap<String, usize>,
+        start: &Rc<String>,
+    ) -> Result<Self> {
+        let is_open_worthy = |v: &String| rates.get(v).is_some_and(|r| *r > 0);
+        let contracted = g.contract(&g.all_pairs_bfs(), |v| {
+            is_open_worthy(v) || start.as_ref().eq(v)
+        });
+
+        let mut valves: Vec<&Rc<String>> = contracted
+            .vertices()
+            .filter(|v| is_open_worthy(v))
+            .collect();
+        if valves.len() >= u64::BITS as usize {
+            return Err(Error::InvalidStruct(
+                "Too many valves to track as opened".to_owned(),
+            ));
+        }
+        valves.sort();
+        valves.push(start);
+
+        let distances = valves
+            .iter()
+            .map(|from| {
+                valves
+                    .iter()
+                    .map(|to| match from == to {
+                        // the start can be a valve worth opening as well
+                        true => Some(0),
+                        false => contracted.get_edge(from, to).map(|d| **d),
+                    })
+                    .collect()
+            })
+            .collect();
+        valves.pop();
+
+        Ok(ValveNetwork {
+            rates: valves.iter().map(|v| rates[v.as_str()]).collect(),
+            distances,
+        })
+    }
+
+    fn start(&self) -> usize {
+        self.rates.len()
+    }
+
+    /// Valves that can still be reached and opened from `pos` with `time` left, along with the
+    /// time remaining once they are open
+    fn openable(
+        &self,
+        pos: usize,
+        time: usize,
+        opened: u64,
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..self.rates.len())
+            .filter(move |next| opened & (1 << next) == 0)
+            .filter_map(move |next| {
+                // one extra minute to open the valve
+                let cost = self.distances[pos][next]? + 1;
+                Some((next, time.checked_sub(cost).filter(|t| *t > 0)?))
+            })
+    }
+
+    /// Most pressure that can be released by a single person in `time` minutes
+    pub fn max_release(&self, time: usize) -> usize {
+        let mut release = Memo::new(|memo, &(pos, time, opened): &(usize, usize, u64)| {
+            self.openable(pos, time, opened)
+                .map(|(next, left)| {
+                    self.rates[next] * left + memo.get((next, left, opened | (1 << next)))
+                })
+                .max()
+                .unwrap_or(0)
+        });
+        release.get((self.start(), time, 0))
+    }
+
+    /// Most pressure that can be released in `time` minutes opening exactly each set of valves,
+    /// only for the sets that can be opened in time.
+    ///
+    /// Paths reaching the same valve with the same time left and the same valves opened are only
+    /// followed further by the one that released the most so far.
+    fn best_per_opened(&self, time: usize) -> HashMap<u64, usize> {
+        let mut best = HashMap::new();
+        let mut seen: HashMap<(usize, usize, u64), usize> = HashMap::new();
+        let mut stack = vec![(self.start(), time, 0u64, 0)];
+        while let Some((pos, time, opened, released)) = stack.pop() {
+            let best_here = seen.entry((pos, time, opened)).or_default();
+            if *best_here > released {
+                continue;
+            }
+            *best_here = released;
+
+            let best_opened = best.entry(opened).or_default();
+            *best_opened = released.max(*best_opened);
+            for (next, left) in self.openable(pos, time, opened) {
+                let released = released + self.rates[next] * left;
+                let state = (next, left, opened | (1 << next));
+                if seen.get(&state).is_none_or(|r| *r < released) {
+                    stack.push((state.0, state.1, state.2, released));
+                }
+            }
+        }
+        best
+    }
+
+    /// Most pressure that can be released in `time` minutes by two people working at the same time.
+    /// Each one opens a different set of valves so the best pair of sets without a valve in common
+    /// is taken.
+    pub fn max_release_with_helper(&self, time: usize) -> usize {
+        let mut best: Vec<(u64, usize)> = self.best_per_opened(time).into_iter().collect();
+        best.sort_unstable_by_key(|(_, released)| Reverse(*released));
+
+        let mut most = 0;
+        for (i, (mine, released)) in best.iter().enumerate() {
+            // the rest is sorted so nothing further can beat what was found
+            if released * 2 < most {
+                break;
+            }
+            for (theirs, helped) in best[i..].iter() {
+                if released + helped <= most {
+                    break;
+                }
+                if mine & theirs == 0 {
+                    most = released + helped;
+                }
+            }
+        }
+        most
+    }
+}
@@ -1,212 +1,271 @@
 use std::{
-    collections::VecDeque,
     fmt::{Debug, Display},
     ops::Range,
 };
 
-use crate::day_17::{moves::Push, shape::Shape};
-
-use super::piece::PieceSpawner;
+use super::{chamber::ChamberConfig, moves::Push, piece::PieceSpawner};
 
+/// The chamber the pieces fall into. Every row is a bit mask where bit `x` is column `x` from the
+/// left wall.
 pub struct BoardIter<I: Iterator<Item = Push>> {
-    top_level: usize,
-    leading_edges: Vec<VecDeque<Range<isize>>>,
+    /// rows from the lowest one kept upwards, the top row always has a rock in it
+    rows: Vec<u8>,
+    /// rows below the deepest level a piece can still reach, they are treated as solid rock
+    pruned: usize,
+    width: usize,
+    /// every column of a row filled in
+    full_row: u8,
+    left_gap: usize,
+    drop_gap: usize,
+    /// row masks and width of every piece pushed against the left wall, indexed by piece
+    masks: Vec<(Vec<u8>, usize)>,
     spawner: PieceSpawner,
     moves_iter: I,
+    /// rendering of every step taken while dropping pieces when tracing
+    trace: Option<String>,
 }
 
 impl<I: Iterator<Item = Push>> BoardIter<I> {
     pub fn new(moves_iter: I) -> Self {
+        Self::with_config(&ChamberConfig::default(), moves_iter)
+    }
+
+    pub fn with_config(config: &ChamberConfig, moves_iter: I) -> Self {
         BoardIter {
-            top_level: 0,
-            // leading edge is the ground for now
-            leading_edges: (0..7).map(|_| VecDeque::from([0..1])).collect(),
-            spawner: PieceSpawner::new(),
+            rows: Vec::new(),
+            pruned: 0,
+            width: config.width(),
+            full_row: (u8::MAX >> (u8::BITS as usize - config.width())),
+            left_gap: config.left_gap(),
+            drop_gap: config.drop_gap(),
+            masks: config
+                .pieces()
+                .iter()
+                .map(|p| (p.row_masks(), p.width()))
+                .collect(),
+            spawner: PieceSpawner::new(config.pieces().len()),
             moves_iter,
+            trace: None,
         }
     }
 
-    fn with_shape<'a>(&'a self, shape: &'a Shape) -> DisplayBoard<'a, I> {
-        DisplayBoard { board: self, shape }
+    /// Records every jet push and fall of the pieces, see [`BoardIter::take_trace`]
+    pub fn with_trace(mut self) -> Self {
+        self.trace = Some(String::new());
+        self
     }
 
-    /// this happens directly after a shape has moved
-    pub fn is_blocked(&self, shape: &Shape) -> bool {
-        // println!("\t{}", shape);
-        // check the edges
-        for p in shape.iter() {
-            if let Some(edges) = self.leading_edges.get(p.x as usize) {
-                for r in edges.iter() {
-                    if r.end <= p.y {
-                        break;
-                    }
-                    if r.contains(&p.y) {
-                        return true;
-                    }
-                }
-            } else {
-                // case where we are reaching beyond bounds
-                return true;
-            }
+    /// The steps recorded since the last time this was called, empty if not tracing
+    pub fn take_trace(&mut self) -> String {
+        self.trace.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    /// Height of the tower, the ground isn't counted
+    pub fn height(&self) -> usize {
+        self.pruned + self.rows.len()
+    }
+
+    /// The rows of the tower that pieces can still reach, lowest first
+    pub fn surface(&self) -> &[u8] {
+        &self.rows
+    }
+
+    /// Index of the piece that will be dropped next
+    pub fn next_piece(&self) -> usize {
+        self.spawner.peek()
+    }
+
+    pub fn moves(&self) -> &I {
+        &self.moves_iter
+    }
+
+    /// Renders the given rows of the tower, the lowest row is 0
+    pub fn window(&self, rows: Range<usize>) -> DisplayBoard<'_, I> {
+        DisplayBoard {
+            board: self,
+            piece: None,
+            rows,
         }
-        return false;
-    }
-
-    /// this happens directly after a shape has been moved back above
-    pub fn fill_in_shape(&mut self, shape: &Shape) {
-        let mut current_top = self.leading_edges[self.top_level].front().unwrap().end;
-        // println!("filling in: \t{}", shape);
-        for p in shape.iter() {
-            let edges = &mut self.leading_edges[p.x as usize];
-            let mut insert_ledge = None;
-            for (i, r) in edges.iter_mut().enumerate() {
-                let diff = p.y + 1 - r.end;
-                if diff > 0 {
-                    if diff.abs() == 1 {
-                        r.end = p.y + 1;
-                    } else {
-                        insert_ledge = Some(i);
-                    }
+    }
+
+    /// Renders the highest `rows` rows of the tower
+    pub fn top(&self, rows: usize) -> DisplayBoard<'_, I> {
+        self.window(self.height().saturating_sub(rows)..self.height())
+    }
+
+    fn is_rock(&self, x: usize, y: usize) -> bool {
+        y.checked_sub(self.pruned)
+            .and_then(|y| self.rows.get(y))
+            .is_some_and(|row| row & (1 << x) != 0)
+    }
+
+    /// Adds a step to the trace showing the falling piece, if any, along with every row that can
+    /// still be reached
+    fn trace_step(&mut self, step: &str, piece: Option<(usize, usize, usize)>) {
+        if self.trace.is_none() {
+            return;
+        }
+        let top = piece
+            .map(|(p, _, y)| y + self.masks[p].0.len())
+            .unwrap_or(0)
+            .max(self.height());
+        let frame = DisplayBoard {
+            board: self,
+            piece: piece.map(|(p, x, y)| (self.masks[p].0.as_slice(), x, y)),
+            rows: self.pruned..top,
+        }
+        .to_string();
+        if let Some(trace) = self.trace.as_mut() {
+            trace.push_str(&format!("{}:\n{}\n", step, frame));
+        }
+    }
+
+    /// Whether the piece collides with rock when its bottom left is at `(x, y)`
+    fn is_blocked(&self, piece: usize, x: usize, y: usize) -> bool {
+        if y < self.pruned {
+            return true;
+        }
+        self.rows
+            .iter()
+            .skip(y - self.pruned)
+            .zip(self.masks[piece].0.iter())
+            .any(|(row, mask)| row & (mask << x) != 0)
+    }
+
+    /// Turns the piece into rock with its bottom left at `(x, y)`
+    fn fill_in_piece(&mut self, piece: usize, x: usize, y: usize) {
+        let piece = &self.masks[piece].0;
+        let bottom = y - self.pruned;
+        if self.rows.len() < bottom + piece.len() {
+            self.rows.resize(bottom + piece.len(), 0);
+        }
+        for (row, mask) in self.rows[bottom..].iter_mut().zip(piece.iter()) {
+            *row |= mask << x;
+        }
+        self.prune();
+    }
+
+    /// Drops every row that can't be reached by sweeping down from the top, moving left, right
+    /// or down through empty space only like the pieces do.
+    fn prune(&mut self) {
+        let mut reachable = self.full_row;
+        for i in (0..self.rows.len()).rev() {
+            let free = !self.rows[i] & self.full_row;
+            let mut row = reachable & free;
+            loop {
+                let spread = (row | row << 1 | row >> 1) & free;
+                if spread == row {
                     break;
                 }
+                row = spread;
             }
-            if let Some(l) = insert_ledge {
-                edges.insert(l, p.y..(p.y + 1));
-            }
-            if let Some(first) = edges.front() {
-                if first.end > current_top {
-                    self.top_level = p.x as usize;
-                    current_top = first.end;
-                }
+            if row == 0 {
+                self.rows.drain(..=i);
+                self.pruned += i + 1;
+                return;
             }
+            reachable = row;
         }
     }
 }
 
 impl<I: Iterator<Item = Push>> Iterator for BoardIter<I> {
-    type Item = isize;
+    type Item = usize;
 
+    /// Drops the next piece until it comes to rest, returning the new height of the tower
     fn next(&mut self) -> Option<Self::Item> {
-        // println!("{}", self);
-        // ground is at zero
-        let current_top = self.leading_edges[self.top_level].front().map(|r| r.end)?;
         let piece = self.spawner.next()?;
-        let mut bounds = piece.bounds();
-        bounds.shift(&(2, current_top + 3).into());
+        let width = self.masks[piece].1;
+
+        let mut x = self.left_gap;
+        let mut y = self.height() + self.drop_gap;
+        self.trace_step("A new rock begins falling", Some((piece, x, y)));
         while let Some(m) = self.moves_iter.next() {
-            // println!("\t{}", self);
-            // println!("\t{}", bounds);
-            bounds.shift(&m.shift_amount());
-            //not inclusive for the bounds
-            if bounds.bottom_right().x > 7 || bounds.bottom_left().x < 0 {
-                bounds.shift(&(-m).shift_amount());
-                continue;
-            }
-            if bounds.bottom_left().y > current_top {
-                // still above top
-                continue;
-            }
             match m {
-                Push::Up => {}
-                Push::Down => {
-                    let shape = bounds.shape(&piece);
-                    // let now = SystemTime::now();
-                    if self.is_blocked(&shape) {
-                        // reverse and solidfy
-                        bounds.shift(&(-m).shift_amount());
-                        let shape = bounds.shape(&piece);
-                        self.fill_in_shape(&shape);
-                        break;
+                Push::Left | Push::Right => {
+                    let to = match m {
+                        Push::Left => x.checked_sub(1),
+                        _ => Some(x + 1).filter(|x| x + width <= self.width),
                     }
-                    // if let Ok(e) = now.elapsed() {
-                    //     if e.as_micros() > 20 {
-                    //         println!("{:?}", e);
-                    //     }
-                    // }
+                    .filter(|x| !self.is_blocked(piece, *x, y));
+                    let step = match (m, to) {
+                        (Push::Left, Some(_)) => "Jet of gas pushes rock left",
+                        (Push::Left, None) => "Jet of gas pushes rock left, but nothing happens",
+                        (_, Some(_)) => "Jet of gas pushes rock right",
+                        (_, None) => "Jet of gas pushes rock right, but nothing happens",
+                    };
+                    x = to.unwrap_or(x);
+                    self.trace_step(step, Some((piece, x, y)));
                 }
-                Push::Right | Push::Left => {
-                    let shape = bounds.shape(&piece);
-                    if self.is_blocked(&shape) {
-                        // reverse and skip
-                        bounds.shift(&(-m).shift_amount());
-                        continue;
+                Push::Down => {
+                    if y == 0 || self.is_blocked(piece, x, y - 1) {
+                        self.fill_in_piece(piece, x, y);
+                        self.trace_step("Rock falls 1 unit, causing it to come to rest", None);
+                        return Some(self.height());
                     }
+                    y -= 1;
+                    self.trace_step("Rock falls 1 unit", Some((piece, x, y)));
                 }
+                Push::Up => {}
             }
         }
-        // println!("{:?}", self);
-
-        self.leading_edges[self.top_level].front().map(|r| r.end)
+        None
     }
 }
 
+/// Renders every row that can still be reached
 impl<I: Iterator<Item = Push>> Display for BoardIter<I> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        // let top = self.top_level.unwrap_or(0) + 4;
-        // for y in (0..top).rev() {
-        //     for x in 0..7 {
-        //         let p: Point = (x, y).into();
-        //         if self.rocks.iter().find(|k| p.eq(k)).is_some() {
-        //             write!(f, "#")?;
-        //         } else {
-        //             write!(f, ".")?;
-        //         }
-        //     }
-        //     writeln!(f)?;
-        // }
-        // for _ in 0..7 {
-        //     write!(f, "-")?;
-        // }
-        // writeln!(f)?;
-        //
-        // Ok(())
-        let mut s = String::new();
-        for i in self.leading_edges.iter() {
-            s.push_str(&format!("{:?}, ", i.front().unwrap()))
-        }
-        if s.len() >= 2 {
-            s.truncate(s.len() - 2);
-        }
-        write!(f, "{}: [{}]", self.top_level, s)
+        write!(f, "{}", self.window(self.pruned..self.height()))
     }
 }
 
 impl<I: Iterator<Item = Push>> Debug for BoardIter<I> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "{}", self.top_level)?;
-        for edges in self.leading_edges.iter() {
-            writeln!(f, "{:?}", edges)?;
-        }
-        Ok(())
+        write!(f, "{}: {:?}", self.pruned, self.rows)
     }
 }
 
+/// Draws rows of the board the way the puzzle does, top row first. Falling rock is `@`, rock at
+/// rest is `#` and rows that were pruned away are drawn as `~`.
 pub struct DisplayBoard<'a, I: Iterator<Item = Push>> {
     board: &'a BoardIter<I>,
-    shape: &'a Shape,
+    /// row masks of the falling piece with its bottom left position
+    piece: Option<(&'a [u8], usize, usize)>,
+    rows: Range<usize>,
+}
+
+impl<'a, I: Iterator<Item = Push>> DisplayBoard<'a, I> {
+    fn is_falling(&self, x: usize, y: usize) -> bool {
+        self.piece.is_some_and(|(masks, px, py)| {
+            y.checked_sub(py)
+                .and_then(|y| masks.get(y))
+                .is_some_and(|mask| (mask << px) & (1 << x) != 0)
+        })
+    }
 }
 
 impl<'a, I: Iterator<Item = Push>> Display for DisplayBoard<'a, I> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        // let top = self.shape.bounds().top_right().y + 3;
-        // for y in (0..top).rev() {
-        //     for x in 0..7 {
-        //         let p: Point = (x, y).into();
-        //         if self.board.rocks.iter().find(|k| p.eq(k)).is_some() {
-        //             write!(f, "#")?;
-        //         } else if self.shape.iter().find(|k| p.eq(k)).is_some() {
-        //             write!(f, "@")?;
-        //         } else {
-        //             write!(f, ".")?;
-        //         }
-        //     }
-        //     writeln!(f)?;
-        // }
-        // for _ in 0..7 {
-        //     write!(f, "-")?;
-        // }
-        // writeln!(f)?;
-        // Ok(())
+        for y in self.rows.clone().rev() {
+            write!(f, "|")?;
+            for x in 0..self.board.width {
+                let c = if self.is_falling(x, y) {
+                    '@'
+                } else if y < self.board.pruned {
+                    '~'
+                } else if self.board.is_rock(x, y) {
+                    '#'
+                } else {
+                    '.'
+                };
+                write!(f, "{}", c)?;
+            }
+            writeln!(f, "|")?;
+        }
+        if self.rows.start == 0 {
+            writeln!(f, "+{}+", "-".repeat(self.board.width))?;
+        }
         Ok(())
     }
 }
@@ -1,6 +1,6 @@
 use std::{cmp::Ordering, fmt::Display};
 
-use super::{piece::Piece, point::Point, shape::Shape};
+use super::point::Point;
 
 /// Constructs a bounding box with the points stored as the bottom left and top right
 #[derive(Debug, Clone, Copy)]
@@ -42,12 +42,6 @@ impl Bounds {
         self.1.x += p.x;
         self.1.y += p.y;
     }
-
-    pub fn shape(&self, piece: &Piece) -> Shape {
-        let mut shape: Shape = piece.points().iter().copied().collect();
-        shape.shift(&self.0);
-        shape
-    }
 }
 
 impl<'a> FromIterator<&'a Point> for Option<Bounds> {
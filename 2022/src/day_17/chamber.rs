@@ -0,0 +1,73 @@
+use advent_of_code::errors::{Error, Result};
+
+use super::piece::{parse_pieces, Piece, DEFAULT_PIECES};
+
+/// Rows of the board are stored as `u8` bit masks
+const MAX_WIDTH: usize = u8::BITS as usize;
+
+/// Describes the chamber the rocks fall into and the rocks that fall.
+#[derive(Debug, Clone)]
+pub struct ChamberConfig {
+    width: usize,
+    /// empty columns between the left wall and a new piece
+    left_gap: usize,
+    /// empty rows between the top of the tower and a new piece
+    drop_gap: usize,
+    pieces: Vec<Piece>,
+}
+
+impl ChamberConfig {
+    /// Builds a chamber `width` columns wide with the pieces drawn as ascii art blocks separated by
+    /// empty lines, see [`Piece`] for the format.
+    ///
+    /// Fails if a piece can't fit into the chamber where it spawns.
+    pub fn new(width: usize, left_gap: usize, drop_gap: usize, pieces: &str) -> Result<Self> {
+        if width == 0 || width > MAX_WIDTH {
+            return Err(Error::InvalidStruct(format!(
+                "Chamber width must be between 1 and {}",
+                MAX_WIDTH
+            )));
+        }
+        let pieces = parse_pieces(pieces)?;
+        if pieces.is_empty() {
+            return Err(Error::InvalidStruct("Chamber needs some pieces".to_owned()));
+        }
+        if let Some(p) = pieces.iter().find(|p| left_gap + p.width() > width) {
+            return Err(Error::InvalidStruct(format!(
+                "Piece {} wide doesn't fit when spawned",
+                p.width()
+            )));
+        }
+
+        Ok(ChamberConfig {
+            width,
+            left_gap,
+            drop_gap,
+            pieces,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn left_gap(&self) -> usize {
+        self.left_gap
+    }
+
+    pub fn drop_gap(&self) -> usize {
+        self.drop_gap
+    }
+
+    pub fn pieces(&self) -> &[Piece] {
+        &self.pieces
+    }
+}
+
+impl Default for ChamberConfig {
+    /// The chamber from the puzzle, 7 wide with the five pieces spawning 2 from the left wall and
+    /// 3 above the tower
+    fn default() -> Self {
+        ChamberConfig::new(7, 2, 3, DEFAULT_PIECES).expect("default chamber is valid")
+    }
+}
@@ -1,51 +1,64 @@
-use std::time::{Duration, SystemTime};
+use advent_of_code::{cycle::find_cycle, errors::Result};
 
-use advent_of_code::errors::Result;
-
-use self::{board::BoardIter, moves::MoveIter};
+use self::{board::BoardIter, chamber::ChamberConfig, moves::MoveIter};
 
 pub mod board;
 mod bounds;
+pub mod chamber;
 mod moves;
 mod piece;
 mod point;
 mod range;
-mod shape;
 
-pub fn solution_pt1<S: AsRef<str>>(s: S, iterations: usize) -> Result<isize> {
+pub fn solution_pt1<S: AsRef<str>>(s: S, iterations: usize) -> Result<usize> {
     let move_iter: MoveIter = s.as_ref().parse()?;
 
-    let seg = 100_000;
-
-    let mut now = SystemTime::now();
     let board = BoardIter::new(move_iter);
-    let top_level = board
-        .enumerate()
-        .inspect(|(i, l)| {
-            if i % seg == 0 {
-                let diff = now.elapsed().unwrap();
-                let speed = diff.as_nanos() / seg as u128;
-                let how_many_left = iterations - i;
-                println!(
-                    "{}: {} | {:?}, ttl: {:?}",
-                    i,
-                    l,
-                    diff,
-                    Duration::from_nanos((how_many_left as u128 * speed) as u64)
-                );
-                now = SystemTime::now();
-            }
-        })
-        .skip(iterations)
-        .map(|(_, l)| l)
-        .next()
-        .ok_or("Couldn't take anymore")?;
-    Ok(top_level - 3)
+    Ok(board.take(iterations).last().unwrap_or(0))
+}
+
+/// Drops far too many pieces to simulate so the board is only run until it starts repeating. The
+/// state of the board is its surface, the next piece and the position in the jets.
+pub fn solution_pt2<S: AsRef<str>>(s: S, pieces: usize) -> Result<usize> {
+    tower_height(s, &ChamberConfig::default(), pieces)
+}
+
+/// Height of the tower after `pieces` have fallen into the chamber described by `config`, found by
+/// extrapolating once the board starts repeating.
+pub fn tower_height<S: AsRef<str>>(s: S, config: &ChamberConfig, pieces: usize) -> Result<usize> {
+    let move_iter: MoveIter = s.as_ref().parse()?;
+    let mut board = BoardIter::with_config(config, move_iter);
+
+    let mut started = false;
+    let cycle = find_cycle(|| {
+        // the empty board is the first state
+        if started {
+            board.next()?;
+        }
+        started = true;
+        let key = (
+            board.surface().to_vec(),
+            board.next_piece(),
+            board.moves().position(),
+        );
+        Some((key, board.height()))
+    })
+    .ok_or("No cycle found")?;
+
+    Ok(cycle
+        .extrapolate(pieces)
+        .ok_or("Too many pieces to extrapolate")?)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::solution_pt1;
+    use super::{
+        board::BoardIter,
+        chamber::ChamberConfig,
+        moves::MoveIter,
+        piece::{parse_pieces, DEFAULT_PIECES},
+        solution_pt1, solution_pt2, tower_height,
+    };
 
     const PAGE_EXAMPLE: &str = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
 
@@ -54,4 +67,96 @@ mod tests {
         let actual = solution_pt1(PAGE_EXAMPLE, 2022).unwrap();
         assert_eq!(actual, 3068)
     }
+
+    #[test]
+    fn page_example_2() {
+        let actual = solution_pt2(PAGE_EXAMPLE, 1_000_000_000_000).unwrap();
+        assert_eq!(actual, 1514285714288)
+    }
+
+    #[test]
+    fn cycle_matches_simulation() {
+        let actual = solution_pt2(PAGE_EXAMPLE, 2022).unwrap();
+        assert_eq!(actual, 3068)
+    }
+
+    #[test]
+    fn unreachable_rows_are_pruned() {
+        let move_iter: MoveIter = PAGE_EXAMPLE.parse().unwrap();
+        let mut board = BoardIter::new(move_iter);
+        let height = board.by_ref().take(100_000).last().unwrap();
+
+        assert_eq!(height, board.height());
+        assert!(board.surface().len() < 100);
+    }
+
+    #[test]
+    fn custom_chamber_matches_simulation() {
+        let config = ChamberConfig::new(5, 1, 2, "##\n#.\n\n###\n\n#").unwrap();
+        let move_iter: MoveIter = PAGE_EXAMPLE.parse().unwrap();
+        let simulated = BoardIter::with_config(&config, move_iter)
+            .take(5000)
+            .last()
+            .unwrap();
+
+        assert_eq!(
+            tower_height(PAGE_EXAMPLE, &config, 5000).unwrap(),
+            simulated
+        );
+    }
+
+    #[test]
+    fn invalid_chambers() {
+        assert!(ChamberConfig::new(9, 2, 3, "#").is_err());
+        assert!(ChamberConfig::new(4, 2, 3, "###").is_err());
+        assert!(ChamberConfig::new(7, 2, 3, "#x#").is_err());
+        assert!(ChamberConfig::new(7, 2, 3, "...").is_err());
+    }
+
+    #[test]
+    fn piece_bounds_from_ascii() {
+        let pieces = parse_pieces(DEFAULT_PIECES).unwrap();
+        let dims: Vec<_> = pieces.iter().map(|p| (p.width(), p.height())).collect();
+
+        assert_eq!(dims, vec![(4, 1), (3, 3), (3, 3), (1, 4), (2, 2)]);
+        assert_eq!(pieces[2].row_masks(), vec![0b111, 0b100, 0b100]);
+        assert_eq!(parse_pieces("..\n.#\n.#").unwrap()[0].width(), 1);
+    }
+
+    #[test]
+    fn renders_tower() {
+        let move_iter: MoveIter = PAGE_EXAMPLE.parse().unwrap();
+        let mut board = BoardIter::new(move_iter);
+        board.by_ref().take(10).for_each(drop);
+
+        let expected = "\
+|....#..|
+|....#..|
+|....##.|
+|##..##.|
+|######.|
+|.###...|
+|..#....|
+|.####..|
+";
+        assert_eq!(board.top(8).to_string(), expected);
+        assert!(board.window(0..2).to_string().ends_with("+-------+\n"));
+    }
+
+    #[test]
+    fn traces_steps() {
+        let move_iter: MoveIter = PAGE_EXAMPLE.parse().unwrap();
+        let mut board = BoardIter::new(move_iter).with_trace();
+        board.next();
+        let trace = board.take_trace();
+
+        assert!(trace.starts_with(
+            "A new rock begins falling:\n|..@@@@.|\n|.......|\n|.......|\n|.......|\n+-------+\n"
+        ));
+        assert!(trace.contains("Jet of gas pushes rock right, but nothing happens:\n"));
+        assert!(trace.contains("Jet of gas pushes rock left:\n|..@@@@.|\n+-------+\n"));
+        assert!(trace
+            .ends_with("Rock falls 1 unit, causing it to come to rest:\n|..####.|\n+-------+\n\n"));
+        assert!(board.take_trace().is_empty());
+    }
 }
@@ -55,6 +55,13 @@ pub struct MoveIter {
     moves: Vec<Push>,
 }
 
+impl MoveIter {
+    /// Index of the next jet along with whether a fall comes before it
+    pub fn position(&self) -> (usize, bool) {
+        (self.cur % self.moves.len().max(1), self.is_down)
+    }
+}
+
 impl Iterator for MoveIter {
     type Item = Push;
 
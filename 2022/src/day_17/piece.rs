@@ -1,100 +1,143 @@
-use super::{bounds::Bounds, point::Point, shape::Shape};
-
-//drafted from the bottom left of the shape
-const FLAT_SHAPE: [Point; 4] = [
-    Point::new_point(0, 0),
-    Point::new_point(1, 0),
-    Point::new_point(2, 0),
-    Point::new_point(3, 0),
-];
-
-const PLUS_SHAPE: [Point; 5] = [
-    Point::new_point(0, 1),
-    Point::new_point(1, 0),
-    Point::new_point(1, 1),
-    Point::new_point(1, 2),
-    Point::new_point(2, 1),
-];
-
-const L_SHAPE: [Point; 5] = [
-    Point::new_point(0, 0),
-    Point::new_point(1, 0),
-    Point::new_point(2, 0),
-    Point::new_point(2, 1),
-    Point::new_point(2, 2),
-];
-
-const VERT_SHAPE: [Point; 4] = [
-    Point::new_point(0, 0),
-    Point::new_point(0, 1),
-    Point::new_point(0, 2),
-    Point::new_point(0, 3),
-];
-
-const SQUARE_SHAPE: [Point; 4] = [
-    Point::new_point(0, 0),
-    Point::new_point(1, 0),
-    Point::new_point(0, 1),
-    Point::new_point(1, 1),
-];
-
-#[derive(Clone, Copy)]
-pub enum Piece {
-    Flat,
-    Plus,
-    L,
-    Vert,
-    Square,
+use std::str::FromStr;
+
+use advent_of_code::errors::{Error, Result};
+
+use super::{bounds::Bounds, point::Point};
+
+/// The pieces from the puzzle in the order they fall
+pub const DEFAULT_PIECES: &str = "\
+####
+
+.#.
+###
+.#.
+
+..#
+..#
+###
+
+#
+#
+#
+#
+
+##
+##";
+
+#[derive(Debug, Clone)]
+pub struct Piece {
+    //drafted from the bottom left of the shape
+    points: Vec<Point>,
+    bounds: Bounds,
 }
 
 impl Piece {
     pub fn points(&self) -> &[Point] {
-        match self {
-            Piece::Flat => &FLAT_SHAPE,
-            Piece::Plus => &PLUS_SHAPE,
-            Piece::L => &L_SHAPE,
-            Piece::Vert => &VERT_SHAPE,
-            Piece::Square => &SQUARE_SHAPE,
-        }
+        &self.points
     }
 
     pub fn bounds(&self) -> Bounds {
-        match self {
-            Piece::Flat => Bounds::zero_based(4, 1),
-            Piece::Plus => Bounds::zero_based(3, 3),
-            Piece::L => Bounds::zero_based(3, 3),
-            Piece::Vert => Bounds::zero_based(1, 4),
-            Piece::Square => Bounds::zero_based(2, 2),
+        self.bounds
+    }
+
+    pub fn width(&self) -> usize {
+        self.bounds.top_right().x as usize
+    }
+
+    pub fn height(&self) -> usize {
+        self.bounds.top_right().y as usize
+    }
+
+    /// One bit mask per row of the piece, bottom row first. Bit `x` is column `x` from the left
+    pub fn row_masks(&self) -> Vec<u8> {
+        let mut rows = vec![0; self.height()];
+        for p in self.points.iter() {
+            rows[p.y as usize] |= 1 << p.x;
         }
+        rows
     }
 }
 
+/// Parses a piece drawn the way the puzzle does, `#` for rock and `.` for empty space with the top
+/// line being the top of the piece. Empty rows and columns around the rock are trimmed off.
+impl FromStr for Piece {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let lines: Vec<&str> = s
+            .lines()
+            .map(|l| l.trim())
+            .filter(|l| !l.is_empty())
+            .collect();
+
+        let mut points = Vec::new();
+        for (y, line) in lines.iter().rev().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                match c {
+                    '#' => points.push(Point::new_point(x as isize, y as isize)),
+                    '.' => {}
+                    c => {
+                        return Err(Error::InvalidParseError(format!(
+                            "{} isn't part of a piece",
+                            c
+                        )))
+                    }
+                }
+            }
+        }
+
+        let bounds = points
+            .iter()
+            .collect::<Option<Bounds>>()
+            .ok_or(Error::InvalidParseError("Piece has no rock".to_owned()))?;
+        let offset = bounds.bottom_left();
+        for p in points.iter_mut() {
+            p.x -= offset.x;
+            p.y -= offset.y;
+        }
+        let bounds = points
+            .iter()
+            .collect::<Option<Bounds>>()
+            .unwrap_or_default();
+
+        Ok(Piece { points, bounds })
+    }
+}
+
+/// Parses every piece from blocks of ascii art separated by empty lines
+pub fn parse_pieces(s: &str) -> Result<Vec<Piece>> {
+    s.split("\n\n")
+        .filter(|block| !block.trim().is_empty())
+        .map(|block| block.parse())
+        .collect()
+}
+
+/// Cycles through the indices of the pieces in order
 pub struct PieceSpawner {
-    current: Piece,
+    current: usize,
+    count: usize,
 }
 
 impl PieceSpawner {
-    pub fn new() -> PieceSpawner {
-        PieceSpawner {
-            current: Piece::Flat,
-        }
+    pub fn new(count: usize) -> PieceSpawner {
+        PieceSpawner { current: 0, count }
+    }
+
+    /// The piece that will be spawned next
+    pub fn peek(&self) -> usize {
+        self.current
     }
 }
 
 impl Iterator for PieceSpawner {
-    type Item = Piece;
+    type Item = usize;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let next = match self.current {
-            Piece::Flat => Piece::Plus,
-            Piece::Plus => Piece::L,
-            Piece::L => Piece::Vert,
-            Piece::Vert => Piece::Square,
-            Piece::Square => Piece::Flat,
-        };
-
+        if self.count == 0 {
+            return None;
+        }
         let out = self.current;
-        self.current = next;
+        self.current = (self.current + 1) % self.count;
         Some(out)
     }
 }
@@ -33,7 +33,7 @@ mod tests {
 
     use crate::{
         day1, day2, day3, day4, day5, day6, day7, day8, day9, day_10, day_11, day_12, day_13,
-        day_14, day_15, day_17,
+        day_14, day_15, day_16, day_17,
     };
 
     #[test]
@@ -248,6 +248,20 @@ mod tests {
         println!("Day 15 Part 2: \n{}", values)
     }
 
+    #[test]
+    fn day_16_pt_1() {
+        let lines = safe_lines("input/day_16.txt").unwrap();
+        let values = day_16::solution_pt1(lines).unwrap();
+        println!("Day 16 Part 1: \n{}", values)
+    }
+
+    #[test]
+    fn day_16_pt_2() {
+        let lines = safe_lines("input/day_16.txt").unwrap();
+        let values = day_16::solution_pt2(lines).unwrap();
+        println!("Day 16 Part 2: \n{}", values)
+    }
+
     #[test]
     fn day_17_pt_1() {
         let line = safe_lines("input/day_17.txt").unwrap().next().unwrap();
@@ -258,7 +272,7 @@ mod tests {
     #[test]
     fn day_17_pt_2() {
         let line = safe_lines("input/day_17.txt").unwrap().next().unwrap();
-        let values = day_17::solution_pt1(line, 1_000_000_000_000).unwrap();
-        println!("Day 17 Part 1: \n{}", values)
+        let values = day_17::solution_pt2(line, 1_000_000_000_000).unwrap();
+        println!("Day 17 Part 2: \n{}", values)
     }
 }
@@ -0,0 +1,469 @@
+//! Fixed size sets of small integers and characters backed by bit masks.
+use std::{
+    fmt::Debug,
+    marker::PhantomData,
+    ops::{BitAnd, BitOr, BitXor, Sub},
+    str::FromStr,
+};
+
+use crate::errors::{Error, Result};
+
+/// Set of the integers `0..N * 64`, every operation is a handful of word operations
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BitSet<const N: usize> {
+    words: [u64; N],
+}
+
+impl<const N: usize> BitSet<N> {
+    pub const CAPACITY: usize = N * 64;
+
+    pub fn new() -> Self {
+        BitSet { words: [0; N] }
+    }
+
+    /// Adds `i`, returns `false` if it was already there. Panics if `i` is outside the capacity
+    pub fn insert(&mut self, i: usize) -> bool {
+        let (word, bit) = Self::locate(i);
+        let had = self.words[word] & bit != 0;
+        self.words[word] |= bit;
+        !had
+    }
+
+    /// Takes `i` out, returns `false` if it wasn't there
+    pub fn remove(&mut self, i: usize) -> bool {
+        if i >= Self::CAPACITY {
+            return false;
+        }
+        let (word, bit) = Self::locate(i);
+        let had = self.words[word] & bit != 0;
+        self.words[word] &= !bit;
+        had
+    }
+
+    /// Adds `i` if it is missing and removes it otherwise, returns whether it is there now
+    pub fn toggle(&mut self, i: usize) -> bool {
+        let (word, bit) = Self::locate(i);
+        self.words[word] ^= bit;
+        self.words[word] & bit != 0
+    }
+
+    pub fn contains(&self, i: usize) -> bool {
+        i < Self::CAPACITY && self.words[i / 64] & (1 << (i % 64)) != 0
+    }
+
+    /// Number of integers in the set
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|w| *w == 0)
+    }
+
+    pub fn clear(&mut self) {
+        self.words = [0; N];
+    }
+
+    /// The smallest integer in the set
+    pub fn first(&self) -> Option<usize> {
+        self.iter().next()
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.words
+            .iter()
+            .zip(other.words.iter())
+            .all(|(a, b)| a & !b == 0)
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a | b)
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a & b)
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a & !b)
+    }
+
+    pub fn symmetric_difference(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a ^ b)
+    }
+
+    /// The integers in increasing order
+    pub fn iter(&self) -> BitSetIter<'_, N> {
+        BitSetIter {
+            words: &self.words,
+            index: 0,
+            current: self.words.first().copied().unwrap_or(0),
+        }
+    }
+
+    fn locate(i: usize) -> (usize, u64) {
+        assert!(
+            i < Self::CAPACITY,
+            "{} doesn't fit in a set of {}",
+            i,
+            Self::CAPACITY
+        );
+        (i / 64, 1 << (i % 64))
+    }
+
+    fn zip_with<F: Fn(u64, u64) -> u64>(&self, other: &Self, f: F) -> Self {
+        let mut out = *self;
+        for (a, b) in out.words.iter_mut().zip(other.words.iter()) {
+            *a = f(*a, *b);
+        }
+        out
+    }
+}
+
+impl<const N: usize> Default for BitSet<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Debug for BitSet<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<const N: usize> FromIterator<usize> for BitSet<N> {
+    fn from_iter<T: IntoIterator<Item = usize>>(iter: T) -> Self {
+        let mut set = Self::new();
+        for i in iter {
+            set.insert(i);
+        }
+        set
+    }
+}
+
+impl<const N: usize> BitOr for BitSet<N> {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        self.union(&rhs)
+    }
+}
+
+impl<const N: usize> BitAnd for BitSet<N> {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        self.intersection(&rhs)
+    }
+}
+
+impl<const N: usize> BitXor for BitSet<N> {
+    type Output = Self;
+
+    fn bitxor(self, rhs: Self) -> Self {
+        self.symmetric_difference(&rhs)
+    }
+}
+
+impl<const N: usize> Sub for BitSet<N> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self.difference(&rhs)
+    }
+}
+
+pub struct BitSetIter<'a, const N: usize> {
+    words: &'a [u64; N],
+    index: usize,
+    /// bits of `words[index]` not yet returned
+    current: u64,
+}
+
+impl<const N: usize> Iterator for BitSetIter<'_, N> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.current == 0 {
+            self.index += 1;
+            self.current = *self.words.get(self.index)?;
+        }
+        let bit = self.current.trailing_zeros() as usize;
+        self.current &= self.current - 1;
+        Some(self.index * 64 + bit)
+    }
+}
+
+/// Decides which index of a [`CharSet`] a character is stored at
+pub trait CharMapping {
+    fn index(c: char) -> Option<usize>;
+    fn char(i: usize) -> Option<char>;
+}
+
+/// `a` to `z` as `0..26`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Lowercase;
+
+/// `A` to `Z` as `0..26`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Uppercase;
+
+/// Rucksack item priorities, `a` to `z` are 1 through 26 and `A` to `Z` are 27 through 52
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Priority;
+
+impl CharMapping for Lowercase {
+    fn index(c: char) -> Option<usize> {
+        c.is_ascii_lowercase().then(|| (c as u8 - b'a') as usize)
+    }
+
+    fn char(i: usize) -> Option<char> {
+        (i < 26).then(|| (b'a' + i as u8) as char)
+    }
+}
+
+impl CharMapping for Uppercase {
+    fn index(c: char) -> Option<usize> {
+        c.is_ascii_uppercase().then(|| (c as u8 - b'A') as usize)
+    }
+
+    fn char(i: usize) -> Option<char> {
+        (i < 26).then(|| (b'A' + i as u8) as char)
+    }
+}
+
+impl CharMapping for Priority {
+    fn index(c: char) -> Option<usize> {
+        Lowercase::index(c)
+            .map(|i| i + 1)
+            .or_else(|| Uppercase::index(c).map(|i| i + 27))
+    }
+
+    fn char(i: usize) -> Option<char> {
+        match i {
+            1..=26 => Lowercase::char(i - 1),
+            27..=52 => Uppercase::char(i - 27),
+            _ => None,
+        }
+    }
+}
+
+/// Set of characters, `M` decides which characters it can hold and where they go in the mask.
+///
+/// ```
+/// use advent_of_code::bitset::{CharSet, Priority};
+///
+/// let first: CharSet<Priority> = "vJrwpWtwJgWr".parse().unwrap();
+/// let second: CharSet<Priority> = "hcsFMMfFFhFp".parse().unwrap();
+/// let shared = first & second;
+/// assert_eq!(shared.iter().collect::<String>(), "p");
+/// assert_eq!(shared.indices().next(), Some(16));
+/// ```
+pub struct CharSet<M> {
+    bits: BitSet<1>,
+    mapping: PhantomData<M>,
+}
+
+impl<M: CharMapping> CharSet<M> {
+    pub fn new() -> Self {
+        CharSet {
+            bits: BitSet::new(),
+            mapping: PhantomData,
+        }
+    }
+
+    /// Adds `c`, returns `false` if it was already there. Fails for characters the mapping
+    /// doesn't cover
+    pub fn insert(&mut self, c: char) -> Result<bool> {
+        Ok(self.bits.insert(Self::index(c)?))
+    }
+
+    pub fn remove(&mut self, c: char) -> bool {
+        M::index(c).is_some_and(|i| self.bits.remove(i))
+    }
+
+    /// Adds `c` if it is missing and removes it otherwise, returns whether it is there now
+    pub fn toggle(&mut self, c: char) -> Result<bool> {
+        Ok(self.bits.toggle(Self::index(c)?))
+    }
+
+    pub fn contains(&self, c: char) -> bool {
+        M::index(c).is_some_and(|i| self.bits.contains(i))
+    }
+
+    pub fn len(&self) -> usize {
+        self.bits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.bits.clear()
+    }
+
+    /// The characters ordered by their index
+    pub fn iter(&self) -> impl Iterator<Item = char> + '_ {
+        self.bits.iter().filter_map(M::char)
+    }
+
+    /// The indices the mapping gave the characters, in increasing order
+    pub fn indices(&self) -> BitSetIter<'_, 1> {
+        self.bits.iter()
+    }
+
+    pub fn bits(&self) -> &BitSet<1> {
+        &self.bits
+    }
+
+    fn index(c: char) -> Result<usize> {
+        M::index(c).ok_or_else(|| Error::InvalidParseError(format!("Can't put {} in the set", c)))
+    }
+
+    fn from_bits(bits: BitSet<1>) -> Self {
+        CharSet {
+            bits,
+            mapping: PhantomData,
+        }
+    }
+}
+
+// derives would put the bounds on `M` which is only a marker
+impl<M> Clone for CharSet<M> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<M> Copy for CharSet<M> {}
+
+impl<M> PartialEq for CharSet<M> {
+    fn eq(&self, other: &Self) -> bool {
+        self.bits == other.bits
+    }
+}
+
+impl<M> Eq for CharSet<M> {}
+
+impl<M: CharMapping> Default for CharSet<M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<M: CharMapping> Debug for CharSet<M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// Characters the mapping doesn't cover are skipped, use [`str::parse`] to have them rejected
+impl<M: CharMapping> FromIterator<char> for CharSet<M> {
+    fn from_iter<T: IntoIterator<Item = char>>(iter: T) -> Self {
+        Self::from_bits(iter.into_iter().filter_map(M::index).collect())
+    }
+}
+
+impl<M: CharMapping> FromStr for CharSet<M> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut set = Self::new();
+        for c in s.chars() {
+            set.insert(c)?;
+        }
+        Ok(set)
+    }
+}
+
+impl<M: CharMapping> BitOr for CharSet<M> {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self::from_bits(self.bits | rhs.bits)
+    }
+}
+
+impl<M: CharMapping> BitAnd for CharSet<M> {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        Self::from_bits(self.bits & rhs.bits)
+    }
+}
+
+impl<M: CharMapping> BitXor for CharSet<M> {
+    type Output = Self;
+
+    fn bitxor(self, rhs: Self) -> Self {
+        Self::from_bits(self.bits ^ rhs.bits)
+    }
+}
+
+impl<M: CharMapping> Sub for CharSet<M> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::from_bits(self.bits - rhs.bits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BitSet, CharMapping, CharSet, Lowercase, Priority, Uppercase};
+
+    #[test]
+    fn bit_set() {
+        let mut set: BitSet<2> = [3, 64, 100].into_iter().collect();
+
+        assert!(set.insert(0));
+        assert!(!set.insert(64));
+        assert!(set.contains(100));
+        assert!(!set.contains(1000));
+        assert_eq!(set.len(), 4);
+        assert!(set.remove(3));
+        assert!(!set.toggle(0));
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![64, 100]);
+        assert_eq!(set.first(), Some(64));
+    }
+
+    #[test]
+    fn set_algebra() {
+        let a: BitSet<1> = [1, 2, 3].into_iter().collect();
+        let b: BitSet<1> = [3, 4].into_iter().collect();
+
+        assert_eq!((a | b).iter().collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+        assert_eq!((a & b).iter().collect::<Vec<_>>(), vec![3]);
+        assert_eq!((a - b).iter().collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!((a ^ b).iter().collect::<Vec<_>>(), vec![1, 2, 4]);
+        assert!((a & b).is_subset(&b));
+        assert!(!a.is_subset(&b));
+    }
+
+    #[test]
+    fn mappings() {
+        for (c, lower, upper, priority) in [('a', Some(0), None, 1), ('Z', None, Some(25), 52)] {
+            assert_eq!(Lowercase::index(c), lower);
+            assert_eq!(Uppercase::index(c), upper);
+            assert_eq!(Priority::index(c), Some(priority));
+            assert_eq!(Priority::char(priority), Some(c));
+        }
+        assert_eq!(Priority::index('!'), None);
+        assert_eq!(Priority::char(0), None);
+    }
+
+    #[test]
+    fn char_set() {
+        let mut set: CharSet<Lowercase> = "hello, world".chars().collect();
+
+        assert_eq!(set.iter().collect::<String>(), "dehlorw");
+        assert!(set.insert('!').is_err());
+        assert!(set.contains('h'));
+        assert!(!set.contains('H'));
+        assert!(set.remove('h'));
+        assert_eq!(set.len(), 6);
+        assert!("hello, world".parse::<CharSet<Lowercase>>().is_err());
+    }
+}
@@ -0,0 +1,168 @@
+//! Cycle detection for simulations that end up repeating themselves.
+//!
+//! Every state of the simulation is reduced to a hashable key along with a metric that is being
+//! tracked (a height, a score...). Once a key shows up a second time the simulation is assumed to
+//! repeat from there on, which lets the metric be extrapolated to any number of steps.
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    ops::{Add, Mul, Sub},
+};
+
+/// A repeat in a simulation, state `start` and state `start + length` have the same key.
+#[derive(Debug, Clone)]
+pub struct Cycle<M> {
+    /// first state of the repeating section
+    pub start: usize,
+    /// number of steps in one repeat
+    pub length: usize,
+    /// metric of every state from 0 up to and including `start + length`
+    history: Vec<M>,
+}
+
+impl<M> Cycle<M>
+where
+    M: Copy + Add<Output = M> + Sub<Output = M> + Mul<Output = M> + TryFrom<usize>,
+{
+    /// How much the metric changes over one repeat
+    pub fn delta(&self) -> M {
+        self.history[self.start + self.length] - self.history[self.start]
+    }
+
+    /// Metric of state `n`. States before the end of the first repeat are read from the recorded
+    /// history, later ones are extrapolated.
+    ///
+    /// Returns `None` if the number of repeats can't be represented as the metric type.
+    pub fn extrapolate(&self, n: usize) -> Option<M> {
+        if let Some(m) = self.history.get(n) {
+            return Some(*m);
+        }
+        let repeats = M::try_from((n - self.start) / self.length).ok()?;
+        let offset = (n - self.start) % self.length;
+        Some(self.history[self.start + offset] + self.delta() * repeats)
+    }
+}
+
+/// Records states as they come until one repeats.
+pub struct CycleDetector<K, M> {
+    seen: HashMap<K, usize>,
+    history: Vec<M>,
+}
+
+impl<K: Hash + Eq, M: Clone> CycleDetector<K, M> {
+    pub fn new() -> Self {
+        CycleDetector {
+            seen: HashMap::new(),
+            history: Vec::new(),
+        }
+    }
+
+    /// Number of states pushed so far
+    pub fn len(&self) -> usize {
+        self.history.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.history.is_empty()
+    }
+
+    /// Records the next state, returning the cycle if `key` has been pushed before.
+    pub fn push(&mut self, key: K, metric: M) -> Option<Cycle<M>> {
+        let index = self.history.len();
+        self.history.push(metric);
+        if let Some(start) = self.seen.insert(key, index) {
+            return Some(Cycle {
+                start,
+                length: index - start,
+                history: self.history.clone(),
+            });
+        }
+        None
+    }
+}
+
+impl<K: Hash + Eq, M: Clone> Default for CycleDetector<K, M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Calls `step` until the key it returns repeats. Each call advances the simulation by one step
+/// and returns the key and metric of the resulting state.
+///
+/// Returns `None` if `step` runs out before anything repeats.
+pub fn find_cycle<K, M, F>(mut step: F) -> Option<Cycle<M>>
+where
+    K: Hash + Eq,
+    M: Clone,
+    F: FnMut() -> Option<(K, M)>,
+{
+    let mut detector = CycleDetector::new();
+    loop {
+        let (key, metric) = step()?;
+        if let Some(cycle) = detector.push(key, metric) {
+            return Some(cycle);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::find_cycle;
+
+    #[test]
+    fn lead_in_then_repeat() {
+        // keys: 0, 1, 2, 3, 4, 2, 3, 4, ... and the metric goes up by the key each step
+        let mut i = 0;
+        let mut total = 0i64;
+        let cycle = find_cycle(|| {
+            let key = if i < 2 { i } else { 2 + (i - 2) % 3 };
+            total += key as i64;
+            i += 1;
+            Some((key, total))
+        })
+        .unwrap();
+
+        assert_eq!(cycle.start, 2);
+        assert_eq!(cycle.length, 3);
+        assert_eq!(cycle.delta(), 9);
+
+        // brute force the same sequence for comparison
+        let brute: Vec<i64> = (0..50)
+            .map(|i| if i < 2 { i } else { 2 + (i - 2) % 3 })
+            .scan(0, |t, k| {
+                *t += k;
+                Some(*t)
+            })
+            .collect();
+        for (n, expected) in brute.iter().enumerate() {
+            assert_eq!(cycle.extrapolate(n), Some(*expected));
+        }
+    }
+
+    #[test]
+    fn no_repeat() {
+        let mut i = 0;
+        let cycle = find_cycle(|| {
+            i += 1;
+            (i < 10).then_some((i, i))
+        });
+
+        assert!(cycle.is_none());
+    }
+
+    #[test]
+    fn huge_extrapolation() {
+        let mut i = 0u64;
+        let cycle = find_cycle(|| {
+            i += 1;
+            Some((i % 4, i))
+        })
+        .unwrap();
+
+        assert_eq!(
+            cycle.extrapolate(1_000_000_000_000),
+            Some(1_000_000_000_001)
+        );
+    }
+}
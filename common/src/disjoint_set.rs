@@ -0,0 +1,185 @@
+//! Union-find over the indices `0..n` for grouping things into connected components.
+
+/// Disjoint set forest with path compression and union by rank.
+#[derive(Debug, Clone)]
+pub struct DisjointSet {
+    parent: Vec<usize>,
+    rank: Vec<u8>,
+    /// number of elements in the component, only correct for roots
+    size: Vec<usize>,
+    components: usize,
+}
+
+impl DisjointSet {
+    /// `n` elements each in their own component
+    pub fn new(n: usize) -> Self {
+        DisjointSet {
+            parent: (0..n).collect(),
+            rank: vec![0; n],
+            size: vec![1; n],
+            components: n,
+        }
+    }
+
+    /// `n` elements joined together by every edge
+    pub fn from_edges<I: IntoIterator<Item = (usize, usize)>>(n: usize, edges: I) -> Self {
+        let mut set = Self::new(n);
+        for (a, b) in edges {
+            set.union(a, b);
+        }
+        set
+    }
+
+    /// Every cell of the grid joined with the neighbours above, below, left and right of it when
+    /// `same` says they belong together.
+    ///
+    /// Cell `(r, c)` is element `r * width + c` where the width is the longest row. Cells missing
+    /// from shorter rows are left on their own.
+    pub fn from_grid<T, F: Fn(&T, &T) -> bool>(grid: &[Vec<T>], same: F) -> Self {
+        let width = grid.iter().map(|row| row.len()).max().unwrap_or(0);
+        let mut set = Self::new(grid.len() * width);
+        for (r, row) in grid.iter().enumerate() {
+            for (c, cell) in row.iter().enumerate() {
+                if let Some(right) = row.get(c + 1) {
+                    if same(cell, right) {
+                        set.union(r * width + c, r * width + c + 1);
+                    }
+                }
+                if let Some(below) = grid.get(r + 1).and_then(|next| next.get(c)) {
+                    if same(cell, below) {
+                        set.union(r * width + c, (r + 1) * width + c);
+                    }
+                }
+            }
+        }
+        set
+    }
+
+    /// Number of elements
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Number of separate components
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+
+    /// The representative of the component `x` is in
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        // point everything on the way straight at the root
+        let mut cur = x;
+        while self.parent[cur] != root {
+            let next = self.parent[cur];
+            self.parent[cur] = root;
+            cur = next;
+        }
+        root
+    }
+
+    /// Joins the components of `a` and `b`, returns `false` if they were already joined
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        let (root, child) = if self.rank[a] < self.rank[b] {
+            (b, a)
+        } else {
+            (a, b)
+        };
+        self.parent[child] = root;
+        self.size[root] += self.size[child];
+        if self.rank[root] == self.rank[child] {
+            self.rank[root] += 1;
+        }
+        self.components -= 1;
+        true
+    }
+
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Number of elements in the component `x` is in
+    pub fn size_of(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+
+    /// Size of every component, largest first
+    pub fn component_sizes(&self) -> Vec<usize> {
+        let mut sizes: Vec<usize> = (0..self.len())
+            .filter(|x| self.parent[*x] == *x)
+            .map(|root| self.size[root])
+            .collect();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        sizes
+    }
+
+    /// The elements of every component in increasing order. Components are ordered by their
+    /// smallest element.
+    pub fn components(&mut self) -> Vec<Vec<usize>> {
+        let mut index = vec![None; self.len()];
+        let mut components: Vec<Vec<usize>> = Vec::with_capacity(self.components);
+        for x in 0..self.len() {
+            let root = self.find(x);
+            let i = *index[root].get_or_insert_with(|| {
+                components.push(Vec::new());
+                components.len() - 1
+            });
+            components[i].push(x);
+        }
+        components
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DisjointSet;
+
+    #[test]
+    fn unions() {
+        let mut set = DisjointSet::from_edges(6, [(0, 1), (1, 2), (4, 5)]);
+
+        assert_eq!(set.component_count(), 3);
+        assert!(set.same(0, 2));
+        assert!(!set.same(2, 3));
+        assert_eq!(set.size_of(1), 3);
+        assert!(!set.union(2, 0));
+        assert!(set.union(3, 5));
+        assert_eq!(set.component_sizes(), vec![3, 3]);
+        assert_eq!(set.components(), vec![vec![0, 1, 2], vec![3, 4, 5]]);
+    }
+
+    #[test]
+    fn long_chain() {
+        let n = 100_000;
+        let mut set = DisjointSet::from_edges(n, (1..n).map(|i| (i - 1, i)));
+
+        assert_eq!(set.component_count(), 1);
+        assert_eq!(set.size_of(0), n);
+    }
+
+    #[test]
+    fn grid_regions() {
+        let grid: Vec<Vec<char>> = ["AAB", "ABB", "CCB"]
+            .iter()
+            .map(|l| l.chars().collect())
+            .collect();
+        let mut set = DisjointSet::from_grid(&grid, |a, b| a == b);
+
+        assert_eq!(set.component_count(), 3);
+        assert_eq!(set.component_sizes(), vec![4, 3, 2]);
+        assert!(set.same(2, 8));
+        assert!(!set.same(0, 4));
+    }
+}
@@ -1,7 +1,4 @@
-use std::{
-    cell::{BorrowError, BorrowMutError},
-    num::ParseIntError,
-};
+use std::num::ParseIntError;
 
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -9,7 +6,6 @@ pub type Result<T> = std::result::Result<T, Error>;
 pub enum Error {
     InvalidParseError(String),
     InvalidStruct(String),
-    BorrowError(String),
     RawError(String),
 }
 
@@ -24,15 +20,3 @@ impl From<&str> for Error {
         Error::RawError(s.to_string())
     }
 }
-
-impl From<BorrowError> for Error {
-    fn from(e: BorrowError) -> Self {
-        Error::BorrowError(format!("{:?}", e))
-    }
-}
-
-impl From<BorrowMutError> for Error {
-    fn from(e: BorrowMutError) -> Self {
-        Error::BorrowError(format!("{:?}", e))
-    }
-}
@@ -0,0 +1,492 @@
+//! Integer arithmetic expressions with variables, parsed from the usual infix notation.
+use std::{
+    borrow::Borrow,
+    collections::{BTreeSet, HashMap},
+    fmt::Display,
+    hash::Hash,
+    iter::Peekable,
+    str::{CharIndices, FromStr},
+};
+
+use crate::errors::{Error, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl Op {
+    /// Applies the operator, division truncates towards zero. Fails on overflow or division by 0
+    pub fn apply(self, l: i64, r: i64) -> Result<i64> {
+        let out = match self {
+            Op::Add => l.checked_add(r),
+            Op::Sub => l.checked_sub(r),
+            Op::Mul => l.checked_mul(r),
+            Op::Div if r == 0 => return Err(Error::RawError("Division by zero".to_owned())),
+            Op::Div => l.checked_div(r),
+        };
+        out.ok_or_else(|| Error::RawError(format!("Overflow evaluating {} {} {}", l, self, r)))
+    }
+
+    fn precedence(self) -> u8 {
+        match self {
+            Op::Add | Op::Sub => 1,
+            Op::Mul | Op::Div => 2,
+        }
+    }
+
+    fn from_char(c: char) -> Option<Op> {
+        match c {
+            '+' => Some(Op::Add),
+            '-' => Some(Op::Sub),
+            '*' => Some(Op::Mul),
+            '/' => Some(Op::Div),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Op {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let c = match self {
+            Op::Add => '+',
+            Op::Sub => '-',
+            Op::Mul => '*',
+            Op::Div => '/',
+        };
+        write!(f, "{}", c)
+    }
+}
+
+/// Syntax tree of an expression like `old * (old - 3) / 2`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Num(i64),
+    Var(String),
+    Neg(Box<Expr>),
+    BinOp(Op, Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    pub fn bin_op(op: Op, l: Expr, r: Expr) -> Expr {
+        Expr::BinOp(op, Box::new(l), Box::new(r))
+    }
+
+    /// Names of every variable used
+    pub fn variables(&self) -> BTreeSet<&str> {
+        let mut out = BTreeSet::new();
+        let mut stack = vec![self];
+        while let Some(e) = stack.pop() {
+            match e {
+                Expr::Num(_) => {}
+                Expr::Var(v) => {
+                    out.insert(v.as_str());
+                }
+                Expr::Neg(e) => stack.push(e),
+                Expr::BinOp(_, l, r) => {
+                    stack.push(l);
+                    stack.push(r);
+                }
+            }
+        }
+        out
+    }
+
+    /// Evaluates with checked arithmetic, fails if a variable is missing from `variables`
+    pub fn eval<K: Borrow<str> + Hash + Eq>(&self, variables: &HashMap<K, i64>) -> Result<i64> {
+        self.fold(variables, &|n| n, &|op, l, r| op.apply(l, r))
+    }
+
+    /// Evaluates in some other number type, `leaf` converts the numbers and variable values and
+    /// `op` combines them. A negation is treated as `0 - x`.
+    pub fn fold<T, K, L, O>(&self, variables: &HashMap<K, i64>, leaf: &L, op: &O) -> Result<T>
+    where
+        K: Borrow<str> + Hash + Eq,
+        L: Fn(i64) -> T,
+        O: Fn(Op, T, T) -> Result<T>,
+    {
+        match self {
+            Expr::Num(n) => Ok(leaf(*n)),
+            Expr::Var(v) => variables
+                .get(v.as_str())
+                .map(|n| leaf(*n))
+                .ok_or_else(|| Error::RawError(format!("Unknown variable {}", v))),
+            Expr::Neg(e) => op(Op::Sub, leaf(0), e.fold(variables, leaf, op)?),
+            Expr::BinOp(o, l, r) => op(
+                *o,
+                l.fold(variables, leaf, op)?,
+                r.fold(variables, leaf, op)?,
+            ),
+        }
+    }
+
+    /// Finds the value of `unknown` that makes the expression equal `target`, every other
+    /// variable is taken from `variables`.
+    ///
+    /// The expression has to be linear in `unknown` and divisions are treated as exact, which is
+    /// how the puzzles are built. Fails if there isn't exactly one integer solution.
+    pub fn solve<K: Borrow<str> + Hash + Eq>(
+        &self,
+        unknown: &str,
+        target: i64,
+        variables: &HashMap<K, i64>,
+    ) -> Result<i64> {
+        let Linear { a, b } = self.linear(unknown, variables)?;
+        if a.is_zero() {
+            return Err(Error::RawError(format!(
+                "Expression doesn't depend on {}",
+                unknown
+            )));
+        }
+        let x = Ratio::from(target as i128)
+            .sub(b)
+            .and_then(|r| r.div(a))
+            .ok_or_else(overflow)?;
+        if x.d != 1 {
+            return Err(Error::RawError(format!(
+                "{} has no integer solution",
+                unknown
+            )));
+        }
+        i64::try_from(x.n).map_err(|_| overflow())
+    }
+
+    /// The expression as `a * unknown + b`
+    fn linear<K: Borrow<str> + Hash + Eq>(
+        &self,
+        unknown: &str,
+        variables: &HashMap<K, i64>,
+    ) -> Result<Linear> {
+        Ok(match self {
+            Expr::Var(v) if v == unknown => Linear {
+                a: Ratio::from(1),
+                b: Ratio::from(0),
+            },
+            Expr::Num(_) | Expr::Var(_) => {
+                Linear::constant(Ratio::from(self.eval(variables)? as i128))
+            }
+            Expr::Neg(e) => e.linear(unknown, variables)?.scale(Ratio::from(-1))?,
+            Expr::BinOp(op, l, r) => {
+                let l = l.linear(unknown, variables)?;
+                let r = r.linear(unknown, variables)?;
+                match op {
+                    Op::Add => Linear {
+                        a: l.a.add(r.a).ok_or_else(overflow)?,
+                        b: l.b.add(r.b).ok_or_else(overflow)?,
+                    },
+                    Op::Sub => Linear {
+                        a: l.a.sub(r.a).ok_or_else(overflow)?,
+                        b: l.b.sub(r.b).ok_or_else(overflow)?,
+                    },
+                    Op::Mul if l.a.is_zero() => r.scale(l.b)?,
+                    Op::Mul if r.a.is_zero() => l.scale(r.b)?,
+                    Op::Mul => return Err(not_linear(unknown)),
+                    Op::Div if !r.a.is_zero() => return Err(not_linear(unknown)),
+                    Op::Div if r.b.is_zero() => {
+                        return Err(Error::RawError("Division by zero".to_owned()))
+                    }
+                    Op::Div => l.scale(Ratio::from(1).div(r.b).ok_or_else(overflow)?)?,
+                }
+            }
+        })
+    }
+}
+
+fn overflow() -> Error {
+    Error::RawError("Overflow solving expression".to_owned())
+}
+
+fn not_linear(unknown: &str) -> Error {
+    Error::RawError(format!("Expression isn't linear in {}", unknown))
+}
+
+/// Exact fraction kept in lowest terms with a positive denominator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Ratio {
+    n: i128,
+    d: i128,
+}
+
+impl Ratio {
+    fn new(n: i128, d: i128) -> Option<Ratio> {
+        if d == 0 {
+            return None;
+        }
+        let (mut a, mut b) = (n.unsigned_abs(), d.unsigned_abs());
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+        let g = (a as i128).max(1) * d.signum();
+        Some(Ratio { n: n / g, d: d / g })
+    }
+
+    fn is_zero(self) -> bool {
+        self.n == 0
+    }
+
+    fn add(self, rhs: Ratio) -> Option<Ratio> {
+        let n = self
+            .n
+            .checked_mul(rhs.d)?
+            .checked_add(rhs.n.checked_mul(self.d)?)?;
+        Ratio::new(n, self.d.checked_mul(rhs.d)?)
+    }
+
+    fn sub(self, rhs: Ratio) -> Option<Ratio> {
+        self.add(Ratio {
+            n: rhs.n.checked_neg()?,
+            d: rhs.d,
+        })
+    }
+
+    fn mul(self, rhs: Ratio) -> Option<Ratio> {
+        Ratio::new(self.n.checked_mul(rhs.n)?, self.d.checked_mul(rhs.d)?)
+    }
+
+    fn div(self, rhs: Ratio) -> Option<Ratio> {
+        Ratio::new(self.n.checked_mul(rhs.d)?, self.d.checked_mul(rhs.n)?)
+    }
+}
+
+impl From<i128> for Ratio {
+    fn from(n: i128) -> Self {
+        Ratio { n, d: 1 }
+    }
+}
+
+/// `a * x + b`
+#[derive(Debug, Clone, Copy)]
+struct Linear {
+    a: Ratio,
+    b: Ratio,
+}
+
+impl Linear {
+    fn constant(b: Ratio) -> Linear {
+        Linear {
+            a: Ratio::from(0),
+            b,
+        }
+    }
+
+    fn scale(self, k: Ratio) -> Result<Linear> {
+        Ok(Linear {
+            a: self.a.mul(k).ok_or_else(overflow)?,
+            b: self.b.mul(k).ok_or_else(overflow)?,
+        })
+    }
+}
+
+/// Parses numbers, variable names made of letters, digits and `_`, the four operators with the
+/// usual precedence, unary minus and parentheses.
+impl FromStr for Expr {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut parser = Parser {
+            s,
+            chars: s.char_indices().peekable(),
+        };
+        let expr = parser.expr(0)?;
+        match parser.peek() {
+            None => Ok(expr),
+            Some((i, c)) => Err(parse_error(c, i)),
+        }
+    }
+}
+
+fn parse_error(c: char, i: usize) -> Error {
+    Error::InvalidParseError(format!("Unexpected {} at {}", c, i))
+}
+
+struct Parser<'a> {
+    s: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+}
+
+impl Parser<'_> {
+    /// Next character that isn't whitespace, without consuming it
+    fn peek(&mut self) -> Option<(usize, char)> {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+        self.chars.peek().copied()
+    }
+
+    /// Precedence climbing, only takes operators binding at least as tight as `min_precedence`
+    fn expr(&mut self, min_precedence: u8) -> Result<Expr> {
+        let mut lhs = self.unary()?;
+        while let Some(op) = self.peek().and_then(|(_, c)| Op::from_char(c)) {
+            if op.precedence() < min_precedence {
+                break;
+            }
+            self.chars.next();
+            let rhs = self.expr(op.precedence() + 1)?;
+            lhs = Expr::bin_op(op, lhs, rhs);
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        match self.peek() {
+            Some((_, '-')) => {
+                self.chars.next();
+                Ok(Expr::Neg(Box::new(self.unary()?)))
+            }
+            _ => self.atom(),
+        }
+    }
+
+    fn atom(&mut self) -> Result<Expr> {
+        let (start, c) = self.peek().ok_or(Error::InvalidParseError(
+            "Expression ended early".to_owned(),
+        ))?;
+        if c == '(' {
+            self.chars.next();
+            let inner = self.expr(0)?;
+            return match self.chars.next() {
+                Some((_, ')')) => Ok(inner),
+                Some((i, c)) => Err(parse_error(c, i)),
+                None => Err(Error::InvalidParseError("Missing )".to_owned())),
+            };
+        }
+        if !(c.is_alphanumeric() || c == '_') {
+            return Err(parse_error(c, start));
+        }
+
+        let mut end = start;
+        while let Some((i, c)) = self
+            .chars
+            .next_if(|(_, c)| c.is_alphanumeric() || *c == '_')
+        {
+            end = i + c.len_utf8();
+        }
+        let word = &self.s[start..end];
+        if c.is_ascii_digit() {
+            Ok(Expr::Num(word.parse()?))
+        } else {
+            Ok(Expr::Var(word.to_owned()))
+        }
+    }
+}
+
+/// Writes the expression back out with only the parentheses it needs
+impl Display for Expr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expr::Num(n) => write!(f, "{}", n),
+            Expr::Var(v) => write!(f, "{}", v),
+            Expr::Neg(e) if matches!(**e, Expr::BinOp(..)) => write!(f, "-({})", e),
+            Expr::Neg(e) => write!(f, "-{}", e),
+            Expr::BinOp(op, l, r) => {
+                let wrap = |e: &Expr, right: bool| match e {
+                    // the right side of - and / changes meaning without them
+                    Expr::BinOp(inner, ..) => {
+                        inner.precedence() < op.precedence()
+                            || (right
+                                && inner.precedence() == op.precedence()
+                                && matches!(op, Op::Sub | Op::Div))
+                    }
+                    _ => false,
+                };
+                if wrap(l, false) {
+                    write!(f, "({})", l)?;
+                } else {
+                    write!(f, "{}", l)?;
+                }
+                write!(f, " {} ", op)?;
+                if wrap(r, true) {
+                    write!(f, "({})", r)
+                } else {
+                    write!(f, "{}", r)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{Expr, Op};
+
+    fn vars(pairs: &[(&'static str, i64)]) -> HashMap<&'static str, i64> {
+        pairs.iter().copied().collect()
+    }
+
+    #[test]
+    fn precedence() {
+        let e: Expr = "1 + 2 * 3 - 8 / 2 / 2".parse().unwrap();
+
+        assert_eq!(e.eval(&vars(&[])), Ok(5));
+        assert_eq!(e.to_string(), "1 + 2 * 3 - 8 / 2 / 2");
+        assert_eq!(
+            "(1 + 2) * -3".parse::<Expr>().unwrap().eval(&vars(&[])),
+            Ok(-9)
+        );
+        assert_eq!(
+            "10 - (4 - 3)".parse::<Expr>().unwrap().to_string(),
+            "10 - (4 - 3)"
+        );
+    }
+
+    #[test]
+    fn variables() {
+        let e: Expr = "old * old + offset".parse().unwrap();
+
+        assert_eq!(
+            e.variables().into_iter().collect::<Vec<_>>(),
+            ["offset", "old"]
+        );
+        assert_eq!(e.eval(&vars(&[("old", 79), ("offset", 2)])), Ok(6243));
+        assert!(e.eval(&vars(&[("old", 79)])).is_err());
+    }
+
+    #[test]
+    fn bad_input() {
+        for s in ["", "1 +", "(1 + 2", "1 2", "a % b", "99999999999999999999"] {
+            assert!(s.parse::<Expr>().is_err(), "{}", s);
+        }
+        let overflow: Expr = "9223372036854775807 + 1".parse().unwrap();
+        assert!(overflow.eval(&vars(&[])).is_err());
+        assert!("1 / (2 - 2)"
+            .parse::<Expr>()
+            .unwrap()
+            .eval(&vars(&[]))
+            .is_err());
+    }
+
+    #[test]
+    fn fold_in_other_types() {
+        let e = Expr::bin_op(Op::Mul, Expr::Var("x".to_owned()), Expr::Num(3));
+        let as_f64 = e.fold(
+            &vars(&[("x", 1)]),
+            &|n| n as f64 / 2.0,
+            &|op, l, r| match op {
+                Op::Mul => Ok(l * r),
+                _ => Err("unused".into()),
+            },
+        );
+
+        assert_eq!(as_f64, Ok(0.75));
+    }
+
+    #[test]
+    fn solve_for_unknown() {
+        // the monkey math example, root's two sides have to be equal
+        let e: Expr = "(4 + 2 * (humn - 3)) / 4 - 150".parse().unwrap();
+        assert_eq!(e.solve("humn", 0, &vars(&[])), Ok(301));
+
+        let twice: Expr = "x + x * y - 10".parse().unwrap();
+        assert_eq!(twice.solve("x", 5, &vars(&[("y", 2)])), Ok(5));
+
+        let squared: Expr = "x * x".parse().unwrap();
+        assert!(squared.solve("x", 4, &vars(&[])).is_err());
+        let constant: Expr = "y + 1".parse().unwrap();
+        assert!(constant.solve("x", 4, &vars(&[("y", 3)])).is_err());
+        let fraction: Expr = "2 * x".parse().unwrap();
+        assert!(fraction.solve("x", 3, &vars(&[])).is_err());
+    }
+}
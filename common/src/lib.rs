@@ -3,9 +3,19 @@ use std::fs::File;
 use std::io::{BufRead, BufReader, Result};
 use std::path::Path;
 
+pub mod bitset;
+pub mod cycle;
+pub mod disjoint_set;
 pub mod errors;
+pub mod expr;
+pub mod memo;
+pub mod number;
+pub mod search;
 pub mod strings;
+pub mod top_k;
+pub mod topo;
 pub mod vec;
+pub mod window;
 
 /// produces an iterator of lines from a file
 /// ignores an error when reading a line and returns early
@@ -0,0 +1,171 @@
+//! Caching for pure functions of hashable arguments, mostly recursive solvers.
+use std::{
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+    rc::Rc,
+};
+
+/// How well the cache of a [`Memo`] has been doing
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub hits: usize,
+    pub misses: usize,
+    /// values dropped to keep the cache within its bound
+    pub evictions: usize,
+}
+
+type Solver<'a, K, V> = Rc<dyn Fn(&mut Memo<'a, K, V>, &K) -> V + 'a>;
+
+/// Wraps a function so each key is only ever computed once.
+///
+/// The function receives the memo itself so it can recurse through [`Memo::get`] and have the
+/// sub problems cached as well.
+///
+/// ```
+/// use advent_of_code::memo::Memo;
+///
+/// let mut fib = Memo::new(|memo, n: &u64| match n {
+///     0 | 1 => *n,
+///     n => memo.get(n - 1) + memo.get(n - 2),
+/// });
+/// assert_eq!(fib.get(90), 2_880_067_194_370_816_120);
+/// ```
+pub struct Memo<'a, K, V> {
+    cache: HashMap<K, V>,
+    /// keys in the order they were cached, only kept when the cache is bounded
+    order: VecDeque<K>,
+    limit: Option<usize>,
+    stats: Stats,
+    solver: Solver<'a, K, V>,
+}
+
+impl<'a, K: Hash + Eq + Clone, V: Clone> Memo<'a, K, V> {
+    pub fn new<F: Fn(&mut Memo<'a, K, V>, &K) -> V + 'a>(solver: F) -> Self {
+        Memo {
+            cache: HashMap::new(),
+            order: VecDeque::new(),
+            limit: None,
+            stats: Stats::default(),
+            solver: Rc::new(solver),
+        }
+    }
+
+    /// Same as [`Memo::new`] but only keeps up to `limit` values, the oldest ones are dropped
+    /// first.
+    pub fn bounded<F: Fn(&mut Memo<'a, K, V>, &K) -> V + 'a>(limit: usize, solver: F) -> Self {
+        let mut memo = Self::new(solver);
+        memo.limit = Some(limit);
+        memo
+    }
+
+    /// The value for `key`, computing it only if it isn't cached
+    pub fn get(&mut self, key: K) -> V {
+        if let Some(v) = self.cache.get(&key) {
+            self.stats.hits += 1;
+            return v.clone();
+        }
+        self.stats.misses += 1;
+        let solver = self.solver.clone();
+        let v = solver(self, &key);
+        self.insert(key, v.clone());
+        v
+    }
+
+    fn insert(&mut self, key: K, v: V) {
+        let Some(limit) = self.limit else {
+            self.cache.insert(key, v);
+            return;
+        };
+        if limit == 0 {
+            return;
+        }
+        while self.cache.len() >= limit {
+            match self.order.pop_front() {
+                Some(old) => {
+                    self.cache.remove(&old);
+                    self.stats.evictions += 1;
+                }
+                None => break,
+            }
+        }
+        // a recursive call might have cached the same key already
+        if self.cache.insert(key.clone(), v).is_none() {
+            self.order.push_back(key);
+        }
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    /// Number of values currently cached
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    /// Drops every cached value, the stats are kept
+    pub fn clear(&mut self) {
+        self.cache.clear();
+        self.order.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::{Memo, Stats};
+
+    #[test]
+    fn computes_each_key_once() {
+        let calls = Cell::new(0);
+        let mut fib = Memo::new(|memo, n: &u64| {
+            calls.set(calls.get() + 1);
+            match n {
+                0 | 1 => *n,
+                n => memo.get(n - 1) + memo.get(n - 2),
+            }
+        });
+
+        assert_eq!(fib.get(50), 12_586_269_025);
+        assert_eq!(fib.get(50), 12_586_269_025);
+        assert_eq!(calls.get(), 51);
+        assert_eq!(
+            fib.stats(),
+            Stats {
+                hits: 49,
+                misses: 51,
+                evictions: 0
+            }
+        );
+    }
+
+    #[test]
+    fn bounded_cache() {
+        let mut square = Memo::bounded(2, |_, n: &u32| n * n);
+        for n in [1, 2, 3, 1] {
+            square.get(n);
+        }
+
+        assert_eq!(square.len(), 2);
+        assert_eq!(square.stats().misses, 4);
+        assert_eq!(square.stats().evictions, 2);
+    }
+
+    #[test]
+    fn tuple_keys() {
+        // number of lattice paths through a grid
+        let mut paths = Memo::new(|memo, &(r, c): &(u32, u32)| -> u64 {
+            if r == 0 || c == 0 {
+                return 1;
+            }
+            memo.get((r - 1, c)) + memo.get((r, c - 1))
+        });
+
+        assert_eq!(paths.get((16, 16)), 601_080_390);
+    }
+}
@@ -0,0 +1,314 @@
+//! Number theory helpers: divisibility, modular arithmetic and the Chinese Remainder Theorem.
+use std::{
+    fmt::Display,
+    ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple, `None` if it doesn't fit in a `u64`
+pub fn lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// Greatest common divisor of every number, 0 when there are none
+pub fn gcd_all<I: IntoIterator<Item = u64>>(numbers: I) -> u64 {
+    numbers.into_iter().fold(0, gcd)
+}
+
+/// Least common multiple of every number, 1 when there are none and `None` if it doesn't fit in a
+/// `u64`
+pub fn lcm_all<I: IntoIterator<Item = u64>>(numbers: I) -> Option<u64> {
+    numbers.into_iter().try_fold(1, lcm)
+}
+
+/// Extended Euclid, returns `(g, x, y)` where `g` is the gcd of `a` and `b` and `a * x + b * y = g`
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (mut old_r, mut r) = (a as i128, b as i128);
+    let (mut old_x, mut x) = (1i128, 0i128);
+    let (mut old_y, mut y) = (0i128, 1i128);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        (old_r, old_x, old_y) = (-old_r, -old_x, -old_y);
+    }
+    (old_r as i64, old_x as i64, old_y as i64)
+}
+
+/// The `x` in `0..m` with `a * x = 1 (mod m)`, `None` if `a` and `m` aren't coprime
+pub fn mod_inverse(a: i64, m: u64) -> Option<u64> {
+    if m == 0 {
+        return None;
+    }
+    let (g, x) = inverse_i128((a as i128).rem_euclid(m as i128), m as i128);
+    if g != 1 {
+        return None;
+    }
+    Some(x.rem_euclid(m as i128) as u64)
+}
+
+/// `(g, x)` with `a * x = g (mod m)`, works with moduli too large for [`extended_gcd`]
+fn inverse_i128(a: i128, m: i128) -> (i128, i128) {
+    let (mut old_r, mut r) = (a, m);
+    let (mut old_x, mut x) = (1, 0);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+    }
+    (old_r, old_x)
+}
+
+/// `a + b (mod m)` without overflowing, panics if `m` is 0
+pub fn mod_add(a: u64, b: u64, m: u64) -> u64 {
+    assert!(m > 0, "modulus has to be more than 0");
+    ((a as u128 + b as u128) % m as u128) as u64
+}
+
+/// `a * b (mod m)` without overflowing, panics if `m` is 0
+pub fn mod_mul(a: u64, b: u64, m: u64) -> u64 {
+    assert!(m > 0, "modulus has to be more than 0");
+    ((a as u128 * b as u128) % m as u128) as u64
+}
+
+/// `base ^ exp (mod m)` by repeated squaring, panics if `m` is 0
+pub fn mod_pow(base: u64, mut exp: u64, m: u64) -> u64 {
+    assert!(m > 0, "modulus has to be more than 0");
+    if m == 1 {
+        return 0;
+    }
+    let mut base = base % m;
+    let mut out = 1;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            out = mod_mul(out, base, m);
+        }
+        base = mod_mul(base, base, m);
+        exp >>= 1;
+    }
+    out
+}
+
+/// Solves `x = r (mod m)` for every `(r, m)` pair, returns `(x, lcm)` with `x` the smallest
+/// solution in `0..lcm` where `lcm` is the least common multiple of the moduli.
+///
+/// The moduli don't have to be coprime. `None` if the congruences contradict each other, a
+/// modulus is 0 or the moduli's lcm doesn't fit in a `u64`.
+pub fn crt<I: IntoIterator<Item = (i64, u64)>>(congruences: I) -> Option<(u64, u64)> {
+    let mut x: i128 = 0;
+    let mut m: i128 = 1;
+    for (r, n) in congruences {
+        if n == 0 {
+            return None;
+        }
+        let n = n as i128;
+        let r = (r as i128).rem_euclid(n);
+        // x + m * k = r (mod n)  =>  m * k = r - x (mod n)
+        let (g, inv) = inverse_i128(m.rem_euclid(n), n);
+        let diff = r - x;
+        if diff % g != 0 {
+            return None;
+        }
+        let step = n / g;
+        // both factors are below `step` which fits in a u64 so the product fits in a u128
+        let k = (diff / g).rem_euclid(step) as u128 * inv.rem_euclid(step) as u128;
+        let k = (k % step as u128) as i128;
+        let next = m.checked_mul(step).filter(|l| *l <= u64::MAX as i128)?;
+        x = (x + m * k).rem_euclid(next);
+        m = next;
+    }
+    Some((x as u64, m as u64))
+}
+
+/// An integer modulo `M`, always kept in `0..M`. `M` has to be more than 0, making a `Mod<0>`
+/// doesn't compile.
+///
+/// ```
+/// use advent_of_code::number::Mod;
+///
+/// let a: Mod<7> = Mod::new(5);
+/// assert_eq!((a * a + Mod::new(3)).value(), 0);
+/// assert_eq!(a.inverse().map(|i| i.value()), Some(3));
+/// ```
+///
+/// ```compile_fail
+/// use advent_of_code::number::Mod;
+///
+/// let zero: Mod<0> = Mod::new(5);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Mod<const M: u64>(u64);
+
+impl<const M: u64> Mod<M> {
+    pub fn new(value: u64) -> Self {
+        const { assert!(M > 0, "Mod<0> has no values") };
+        Mod(value % M)
+    }
+
+    pub fn value(self) -> u64 {
+        self.0
+    }
+
+    pub fn pow(self, exp: u64) -> Self {
+        Mod(mod_pow(self.0, exp, M))
+    }
+
+    /// The value that multiplies with this one to 1, `None` if it isn't coprime with `M`
+    pub fn inverse(self) -> Option<Self> {
+        let (g, x) = inverse_i128(self.0 as i128, M as i128);
+        (g == 1).then(|| Mod(x.rem_euclid(M as i128) as u64))
+    }
+}
+
+impl<const M: u64> From<u64> for Mod<M> {
+    fn from(value: u64) -> Self {
+        Mod::new(value)
+    }
+}
+
+impl<const M: u64> Default for Mod<M> {
+    fn default() -> Self {
+        Mod::new(0)
+    }
+}
+
+impl<const M: u64> From<i64> for Mod<M> {
+    fn from(value: i64) -> Self {
+        const { assert!(M > 0, "Mod<0> has no values") };
+        Mod((value as i128).rem_euclid(M as i128) as u64)
+    }
+}
+
+impl<const M: u64> Add for Mod<M> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Mod(mod_add(self.0, rhs.0, M))
+    }
+}
+
+impl<const M: u64> Sub for Mod<M> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl<const M: u64> Mul for Mod<M> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Mod(mod_mul(self.0, rhs.0, M))
+    }
+}
+
+impl<const M: u64> Neg for Mod<M> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Mod((M - self.0) % M)
+    }
+}
+
+impl<const M: u64> AddAssign for Mod<M> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<const M: u64> SubAssign for Mod<M> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<const M: u64> MulAssign for Mod<M> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl<const M: u64> Display for Mod<M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{crt, extended_gcd, gcd_all, lcm_all, mod_inverse, mod_mul, mod_pow, Mod};
+
+    #[test]
+    fn divisibility() {
+        assert_eq!(gcd_all([12, 18, 30]), 6);
+        assert_eq!(gcd_all([]), 0);
+        assert_eq!(lcm_all([4, 6, 10]), Some(60));
+        assert_eq!(lcm_all([23, 19, 13, 17]), Some(96577));
+        assert_eq!(lcm_all([u64::MAX, 2]), None);
+    }
+
+    #[test]
+    fn extended_euclid() {
+        let (g, x, y) = extended_gcd(240, 46);
+        assert_eq!(g, 2);
+        assert_eq!(240 * x + 46 * y, 2);
+
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+    }
+
+    #[test]
+    fn large_powers() {
+        let m = (1 << 61) - 1;
+        assert_eq!(mod_pow(2, 61, m), 1);
+        assert_eq!(mod_pow(3, m - 1, m), 1);
+        assert_eq!(mod_pow(5, 0, 1), 0);
+    }
+
+    #[test]
+    fn chinese_remainder() {
+        assert_eq!(crt([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        // moduli sharing factors
+        assert_eq!(crt([(3, 4), (5, 6)]), Some((11, 12)));
+        assert_eq!(crt([(1, 4), (2, 6)]), None);
+        assert_eq!(crt([(-1, 7), (0, 13)]), Some((13, 91)));
+        assert_eq!(crt([]), Some((0, 1)));
+        let big = (1 << 61) - 1;
+        assert_eq!(crt([(6, big), (1, 2)]), Some((big + 6, 2 * big)));
+    }
+
+    #[test]
+    fn modular_newtype() {
+        type M = Mod<1_000_000_007>;
+        let a = M::new(1_000_000_000);
+        let b = M::from(-8i64);
+
+        assert_eq!((a - b).value(), 1_000_000_008 % 1_000_000_007);
+        assert_eq!((a * a).value(), 49);
+        let mut c = a;
+        c *= a.inverse().unwrap();
+        assert_eq!(c, M::new(1));
+        assert_eq!((-M::new(0)).value(), 0);
+        assert_eq!(M::new(2).pow(10).to_string(), "1024");
+    }
+
+    #[test]
+    #[should_panic(expected = "modulus has to be more than 0")]
+    fn zero_modulus() {
+        mod_mul(2, 3, 0);
+    }
+}
@@ -0,0 +1,330 @@
+//! Shortest path searches over implicit state spaces.
+//!
+//! The graph is never built up front, instead a `successors` closure produces the neighbouring
+//! states of a state together with the cost of moving to it. Costs can be any integer like type,
+//! `Default` is used as the zero cost.
+use std::{
+    cmp::Ordering,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+/// Heap entry ordered so the [`BinaryHeap`] pops the smallest estimate first. Ties go to the entry
+/// that has travelled the furthest as it is most likely to be closest to the goal.
+struct Candidate<C> {
+    estimate: C,
+    cost: C,
+    index: usize,
+}
+
+impl<C: Ord> PartialEq for Candidate<C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<C: Ord> Eq for Candidate<C> {}
+
+impl<C: Ord> Ord for Candidate<C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .estimate
+            .cmp(&self.estimate)
+            .then_with(|| self.cost.cmp(&other.cost))
+    }
+}
+
+impl<C: Ord> PartialOrd for Candidate<C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Every state that has been reached, with the best known cost and where it was reached from
+struct Explored<S, C> {
+    nodes: Vec<(S, Option<usize>, C)>,
+    indices: HashMap<S, usize>,
+}
+
+impl<S: Clone + Hash + Eq, C: Copy + Ord> Explored<S, C> {
+    fn new(start: S, zero: C) -> Self {
+        Explored {
+            nodes: vec![(start.clone(), None, zero)],
+            indices: HashMap::from([(start, 0)]),
+        }
+    }
+
+    /// Records `state` as reachable with `cost` from `parent`. Returns the index of the state if
+    /// this is an improvement over what was known.
+    fn relax(&mut self, state: S, parent: usize, cost: C) -> Option<usize> {
+        match self.indices.entry(state) {
+            Entry::Vacant(e) => {
+                let index = self.nodes.len();
+                self.nodes.push((e.key().clone(), Some(parent), cost));
+                e.insert(index);
+                Some(index)
+            }
+            Entry::Occupied(e) => {
+                let index = *e.get();
+                let node = &mut self.nodes[index];
+                if cost >= node.2 {
+                    return None;
+                }
+                node.1 = Some(parent);
+                node.2 = cost;
+                Some(index)
+            }
+        }
+    }
+
+    fn path(&self, mut index: usize) -> Vec<S> {
+        let mut path = vec![self.nodes[index].0.clone()];
+        while let Some(parent) = self.nodes[index].1 {
+            path.push(self.nodes[parent].0.clone());
+            index = parent;
+        }
+        path.reverse();
+        path
+    }
+}
+
+/// A* search from `start` to the first state that satisfies `goal`.
+///
+/// `heuristic` must never overestimate the remaining cost for the returned path to be the
+/// shortest. Returns the total cost and the path including both `start` and the goal state, or
+/// `None` if no goal state can be reached.
+pub fn astar<S, C, FN, IN, FG, FH>(
+    start: S,
+    mut successors: FN,
+    mut goal: FG,
+    mut heuristic: FH,
+) -> Option<(C, Vec<S>)>
+where
+    S: Clone + Hash + Eq,
+    C: Copy + Ord + Default + Add<Output = C>,
+    FN: FnMut(&S) -> IN,
+    IN: IntoIterator<Item = (S, C)>,
+    FG: FnMut(&S) -> bool,
+    FH: FnMut(&S) -> C,
+{
+    let zero = C::default();
+    let mut heap = BinaryHeap::new();
+    heap.push(Candidate {
+        estimate: heuristic(&start),
+        cost: zero,
+        index: 0,
+    });
+    let mut explored = Explored::new(start, zero);
+
+    while let Some(Candidate { cost, index, .. }) = heap.pop() {
+        let (state, _, best) = &explored.nodes[index];
+        if cost > *best {
+            // a cheaper way here has already been expanded
+            continue;
+        }
+        if goal(state) {
+            return Some((cost, explored.path(index)));
+        }
+        let state = state.clone();
+        for (next, step) in successors(&state) {
+            let next_cost = cost + step;
+            let estimate = next_cost + heuristic(&next);
+            if let Some(next_index) = explored.relax(next, index, next_cost) {
+                heap.push(Candidate {
+                    estimate,
+                    cost: next_cost,
+                    index: next_index,
+                });
+            }
+        }
+    }
+
+    None
+}
+
+/// Dijkstra search from `start` to the first state that satisfies `goal`.
+///
+/// Same as [`astar`] without a heuristic.
+pub fn dijkstra<S, C, FN, IN, FG>(start: S, successors: FN, goal: FG) -> Option<(C, Vec<S>)>
+where
+    S: Clone + Hash + Eq,
+    C: Copy + Ord + Default + Add<Output = C>,
+    FN: FnMut(&S) -> IN,
+    IN: IntoIterator<Item = (S, C)>,
+    FG: FnMut(&S) -> bool,
+{
+    astar(start, successors, goal, |_| C::default())
+}
+
+/// Shortest cost from `start` to every reachable state, `start` itself included with a zero cost.
+pub fn dijkstra_all<S, C, FN, IN>(start: S, mut successors: FN) -> HashMap<S, C>
+where
+    S: Clone + Hash + Eq,
+    C: Copy + Ord + Default + Add<Output = C>,
+    FN: FnMut(&S) -> IN,
+    IN: IntoIterator<Item = (S, C)>,
+{
+    let zero = C::default();
+    let mut heap = BinaryHeap::new();
+    heap.push(Candidate {
+        estimate: zero,
+        cost: zero,
+        index: 0,
+    });
+    let mut explored = Explored::new(start, zero);
+
+    while let Some(Candidate { cost, index, .. }) = heap.pop() {
+        let (state, _, best) = &explored.nodes[index];
+        if cost > *best {
+            continue;
+        }
+        let state = state.clone();
+        for (next, step) in successors(&state) {
+            let next_cost = cost + step;
+            if let Some(next_index) = explored.relax(next, index, next_cost) {
+                heap.push(Candidate {
+                    estimate: next_cost,
+                    cost: next_cost,
+                    index: next_index,
+                });
+            }
+        }
+    }
+
+    explored
+        .nodes
+        .into_iter()
+        .map(|(state, _, cost)| (state, cost))
+        .collect()
+}
+
+/// Breadth first search from every state in `starts` at once to the closest state that satisfies
+/// `goal`, every step costing the same.
+///
+/// Returns the path including the start it came from and the goal state, or `None` if no goal
+/// state can be reached.
+pub fn bfs<S, I, FN, IN, FG>(starts: I, mut successors: FN, mut goal: FG) -> Option<Vec<S>>
+where
+    S: Clone + Hash + Eq,
+    I: IntoIterator<Item = S>,
+    FN: FnMut(&S) -> IN,
+    IN: IntoIterator<Item = S>,
+    FG: FnMut(&S) -> bool,
+{
+    let mut nodes: Vec<(S, Option<usize>)> = Vec::new();
+    let mut seen = HashMap::new();
+    for start in starts {
+        if let Entry::Vacant(e) = seen.entry(start) {
+            nodes.push((e.key().clone(), None));
+            e.insert(nodes.len() - 1);
+        }
+    }
+    let mut queue: VecDeque<usize> = (0..nodes.len()).collect();
+
+    while let Some(index) = queue.pop_front() {
+        let state = nodes[index].0.clone();
+        if goal(&state) {
+            let mut path = vec![state];
+            let mut cur = index;
+            while let Some(parent) = nodes[cur].1 {
+                path.push(nodes[parent].0.clone());
+                cur = parent;
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for next in successors(&state) {
+            if let Entry::Vacant(e) = seen.entry(next) {
+                nodes.push((e.key().clone(), Some(index)));
+                e.insert(nodes.len() - 1);
+                queue.push_back(nodes.len() - 1);
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{astar, bfs, dijkstra, dijkstra_all};
+
+    // A -1-> B -1-> C -1-> D
+    //  \----------5------/
+    fn successors(s: &char) -> Vec<(char, u32)> {
+        match s {
+            'A' => vec![('B', 1), ('D', 5)],
+            'B' => vec![('C', 1)],
+            'C' => vec![('D', 1)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn picks_cheapest_path() {
+        let (cost, path) = dijkstra('A', successors, |s| *s == 'D').unwrap();
+
+        assert_eq!(cost, 3);
+        assert_eq!(path, vec!['A', 'B', 'C', 'D']);
+    }
+
+    #[test]
+    fn unreachable_goal() {
+        assert!(dijkstra('B', successors, |s| *s == 'A').is_none());
+    }
+
+    #[test]
+    fn start_is_goal() {
+        let (cost, path) = dijkstra('A', successors, |s| *s == 'A').unwrap();
+
+        assert_eq!(cost, 0);
+        assert_eq!(path, vec!['A']);
+    }
+
+    #[test]
+    fn astar_on_grid() {
+        // 10x10 open grid, manhattan distance is exact
+        let neighbours = |&(x, y): &(i32, i32)| {
+            [(1, 0), (-1, 0), (0, 1), (0, -1)]
+                .into_iter()
+                .map(move |(dx, dy)| (x + dx, y + dy))
+                .filter(|(x, y)| (0..10).contains(x) && (0..10).contains(y))
+                .map(|p| (p, 1))
+        };
+        let (cost, path) = astar(
+            (0, 0),
+            neighbours,
+            |p| *p == (9, 9),
+            |(x, y)| (9 - x) + (9 - y),
+        )
+        .unwrap();
+
+        assert_eq!(cost, 18);
+        assert_eq!(path.len(), 19);
+    }
+
+    #[test]
+    fn all_distances() {
+        let distances = dijkstra_all('A', successors);
+
+        assert_eq!(distances.len(), 4);
+        assert_eq!(distances[&'A'], 0);
+        assert_eq!(distances[&'C'], 2);
+        assert_eq!(distances[&'D'], 3);
+    }
+
+    #[test]
+    fn bfs_closest_start() {
+        // number line, stepping by one in either direction
+        let path = bfs([0, 7, 20], |n: &i32| [n - 1, n + 1], |n| *n == 10).unwrap();
+
+        assert_eq!(path, vec![7, 8, 9, 10]);
+        assert!(bfs(
+            [0],
+            |n: &i32| [n + 1].into_iter().filter(|n| *n < 5),
+            |n| *n == 10
+        )
+        .is_none());
+    }
+}
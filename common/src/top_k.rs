@@ -0,0 +1,170 @@
+//! Keeping only the largest few items of a sequence.
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+};
+
+/// Collects the `k` largest items pushed into it, never holding more than `k` at once.
+///
+/// ```
+/// use advent_of_code::top_k::{TopK, TopKExt};
+///
+/// assert_eq!([5, 1, 8, 3, 9].into_iter().top_k(3), vec![9, 8, 5]);
+///
+/// let mut top = TopK::new(2);
+/// top.extend(["pear", "fig", "banana"].map(str::len));
+/// assert_eq!(top.into_sorted_vec(), vec![6, 4]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TopK<T> {
+    k: usize,
+    /// smallest of the kept items on top so it can be swapped out
+    heap: BinaryHeap<Reverse<T>>,
+}
+
+impl<T: Ord> TopK<T> {
+    pub fn new(k: usize) -> Self {
+        TopK {
+            k,
+            heap: BinaryHeap::with_capacity(k + 1),
+        }
+    }
+
+    /// Keeps `item` if it is larger than the smallest item kept so far, `O(log k)`
+    pub fn push(&mut self, item: T) {
+        if self.heap.len() < self.k {
+            self.heap.push(Reverse(item));
+        } else if let Some(mut smallest) = self.heap.peek_mut() {
+            if item > smallest.0 {
+                smallest.0 = item;
+            }
+        }
+    }
+
+    /// Number of items kept, at most `k`
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// The smallest item kept, any item that isn't larger won't be kept
+    pub fn threshold(&self) -> Option<&T> {
+        self.heap.peek().map(|r| &r.0)
+    }
+
+    /// The kept items, largest first
+    pub fn into_sorted_vec(self) -> Vec<T> {
+        // sorting the reversed items ascending puts the largest first
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|r| r.0)
+            .collect()
+    }
+}
+
+impl<T: Ord> Extend<T> for TopK<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+/// Orders items only by their key, earlier items rank higher among equal keys
+struct Keyed<K, T> {
+    key: K,
+    order: Reverse<usize>,
+    item: T,
+}
+
+impl<K: Ord, T> Ord for Keyed<K, T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.key, self.order).cmp(&(&other.key, other.order))
+    }
+}
+
+impl<K: Ord, T> PartialOrd for Keyed<K, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K: Ord, T> PartialEq for Keyed<K, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<K: Ord, T> Eq for Keyed<K, T> {}
+
+pub trait TopKExt: Iterator + Sized {
+    /// The `k` largest items, largest first
+    fn top_k(self, k: usize) -> Vec<Self::Item>
+    where
+        Self::Item: Ord,
+    {
+        let mut top = TopK::new(k);
+        top.extend(self);
+        top.into_sorted_vec()
+    }
+
+    /// The `k` items with the largest keys, largest first. Items with equal keys stay in the
+    /// order they came in.
+    fn top_k_by_key<K: Ord, F: FnMut(&Self::Item) -> K>(
+        self,
+        k: usize,
+        mut f: F,
+    ) -> Vec<Self::Item> {
+        let mut top = TopK::new(k);
+        top.extend(self.enumerate().map(|(i, item)| Keyed {
+            key: f(&item),
+            order: Reverse(i),
+            item,
+        }));
+        top.into_sorted_vec().into_iter().map(|k| k.item).collect()
+    }
+}
+
+impl<I: Iterator> TopKExt for I {}
+
+#[cfg(test)]
+mod tests {
+    use super::{TopK, TopKExt};
+
+    #[test]
+    fn keeps_largest() {
+        let mut top = TopK::new(3);
+        top.extend([4, 9, 1, 9, 7, 3]);
+
+        assert_eq!(top.len(), 3);
+        assert_eq!(top.threshold(), Some(&7));
+        assert_eq!(top.into_sorted_vec(), vec![9, 9, 7]);
+    }
+
+    #[test]
+    fn fewer_than_k() {
+        assert_eq!([2, 1].into_iter().top_k(5), vec![2, 1]);
+        assert_eq!([2, 1].into_iter().top_k(0), Vec::<i32>::new());
+    }
+
+    #[test]
+    fn by_key() {
+        let words = ["fig", "apple", "kiwi", "melon", "plum"];
+        let longest = words.into_iter().top_k_by_key(3, |w| w.len());
+
+        assert_eq!(longest, vec!["apple", "melon", "kiwi"]);
+    }
+
+    #[test]
+    fn matches_sorting() {
+        let items: Vec<u64> = (0..1000u64).map(|i| i * 7919 % 1009).collect();
+        let mut sorted = items.clone();
+        sorted.sort_unstable_by(|a, b| b.cmp(a));
+
+        assert_eq!(items.into_iter().top_k(25), sorted[..25]);
+    }
+}
@@ -0,0 +1,170 @@
+//! Ordering things that depend on each other.
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet, BinaryHeap},
+    fmt::Debug,
+};
+
+use crate::errors::Error;
+
+/// The dependencies loop back on themselves. Holds the vertices of one of the loops starting from
+/// the smallest, each one has to come before the next and the last before the first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleError<T>(pub Vec<T>);
+
+impl<T: Debug> From<CycleError<T>> for Error {
+    fn from(e: CycleError<T>) -> Self {
+        Error::InvalidStruct(format!("Dependency cycle between {:?}", e.0))
+    }
+}
+
+/// Orders `vertices` so that for every edge `(a, b)` `a` comes before `b`, using Kahn's algorithm.
+///
+/// When several vertices are free to go next the smallest one goes first so the order is always
+/// the same. Vertices only named by an edge are included as well.
+pub fn topological_sort<T, V, E>(vertices: V, edges: E) -> Result<Vec<T>, CycleError<T>>
+where
+    T: Ord + Clone,
+    V: IntoIterator<Item = T>,
+    E: IntoIterator<Item = (T, T)>,
+{
+    let mut successors: BTreeMap<T, BTreeSet<T>> =
+        vertices.into_iter().map(|v| (v, BTreeSet::new())).collect();
+    let mut in_degree: BTreeMap<T, usize> = successors.keys().map(|v| (v.clone(), 0)).collect();
+    for (a, b) in edges {
+        in_degree.entry(a.clone()).or_default();
+        successors.entry(b.clone()).or_default();
+        if successors.entry(a).or_default().insert(b.clone()) {
+            *in_degree.entry(b).or_default() += 1;
+        }
+    }
+
+    let mut ready: BinaryHeap<Reverse<T>> = in_degree
+        .iter()
+        .filter(|(_, d)| **d == 0)
+        .map(|(v, _)| Reverse(v.clone()))
+        .collect();
+    let mut order = Vec::with_capacity(in_degree.len());
+    while let Some(Reverse(v)) = ready.pop() {
+        for next in successors[&v].iter() {
+            let d = in_degree
+                .get_mut(next)
+                .expect("every successor has a degree");
+            *d -= 1;
+            if *d == 0 {
+                ready.push(Reverse(next.clone()));
+            }
+        }
+        order.push(v);
+    }
+
+    if order.len() < in_degree.len() {
+        return Err(find_cycle(&successors, &in_degree));
+    }
+    Ok(order)
+}
+
+/// Every vertex left with a non zero in degree has a predecessor that is also left, so walking
+/// backwards through them has to come around to a vertex seen before.
+fn find_cycle<T: Ord + Clone>(
+    successors: &BTreeMap<T, BTreeSet<T>>,
+    in_degree: &BTreeMap<T, usize>,
+) -> CycleError<T> {
+    let left: BTreeSet<&T> = in_degree
+        .iter()
+        .filter(|(_, d)| **d > 0)
+        .map(|(v, _)| v)
+        .collect();
+    let predecessor = |v: &T| {
+        successors
+            .iter()
+            .find(|(from, to)| left.contains(from) && to.contains(v))
+            .map(|(from, _)| from)
+    };
+
+    let mut path: Vec<&T> = Vec::new();
+    let mut cur = left.first().copied();
+    while let Some(v) = cur {
+        if let Some(start) = path.iter().position(|p| *p == v) {
+            let mut cycle: Vec<T> = path[start..].iter().map(|v| (*v).clone()).collect();
+            cycle.reverse();
+            let smallest = (0..cycle.len()).min_by_key(|i| &cycle[*i]).unwrap_or(0);
+            cycle.rotate_left(smallest);
+            return CycleError(cycle);
+        }
+        path.push(v);
+        cur = predecessor(v);
+    }
+    CycleError(path.into_iter().cloned().collect())
+}
+
+/// Reorders `items` so every rule `(a, b)` with both `a` and `b` in `items` has `a` before `b`.
+/// Rules about anything else are ignored.
+pub fn sort_by_rules<T, R>(items: &mut [T], rules: R) -> Result<(), CycleError<T>>
+where
+    T: Ord + Clone,
+    R: IntoIterator<Item = (T, T)>,
+{
+    let mut counts: BTreeMap<T, usize> = BTreeMap::new();
+    for item in items.iter() {
+        *counts.entry(item.clone()).or_default() += 1;
+    }
+    let rules: Vec<(T, T)> = rules
+        .into_iter()
+        .filter(|(a, b)| a != b && counts.contains_key(a) && counts.contains_key(b))
+        .collect();
+
+    let order = topological_sort(counts.keys().cloned(), rules)?;
+    let sorted = order
+        .into_iter()
+        .flat_map(|v| std::iter::repeat_n(v.clone(), counts[&v]));
+    for (slot, v) in items.iter_mut().zip(sorted) {
+        *slot = v;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sort_by_rules, topological_sort, CycleError};
+
+    #[test]
+    fn ordered_with_ties_broken() {
+        let order = topological_sort(['e'], [('c', 'a'), ('c', 'b'), ('b', 'a'), ('d', 'a')]);
+
+        assert_eq!(order, Ok(vec!['c', 'b', 'd', 'a', 'e']));
+    }
+
+    #[test]
+    fn reports_cycle() {
+        let edges = [(1, 2), (2, 3), (3, 4), (4, 2), (4, 5)];
+        let CycleError(cycle) = topological_sort([], edges).unwrap_err();
+
+        assert_eq!(cycle, vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn self_loop() {
+        assert_eq!(topological_sort([1], [(1, 1)]), Err(CycleError(vec![1])));
+    }
+
+    #[test]
+    fn rules() {
+        let rules = [
+            (47, 53),
+            (97, 13),
+            (97, 61),
+            (97, 47),
+            (75, 29),
+            (61, 13),
+            (75, 53),
+        ];
+        let mut update = vec![75, 97, 47, 61, 53];
+        sort_by_rules(&mut update, rules).unwrap();
+
+        assert_eq!(update, vec![75, 97, 47, 53, 61]);
+
+        let mut looping = vec![1, 2];
+        assert!(sort_by_rules(&mut looping, [(1, 2), (2, 1)]).is_err());
+    }
+}
@@ -0,0 +1,246 @@
+//! Finding runs of consecutive items that are (almost) all different from each other.
+use std::{
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+    marker::PhantomData,
+};
+
+/// Keeps count of how many times each item is in the window
+pub trait Counter<T> {
+    /// Counts one more `item`, returns how many there are now
+    fn add(&mut self, item: T) -> usize;
+    /// Counts one less `item`, returns how many are left
+    fn remove(&mut self, item: T) -> usize;
+}
+
+/// Counts any hashable item
+#[derive(Debug, Clone, Default)]
+pub struct HashCounter<T>(HashMap<T, usize>);
+
+impl<T: Copy + Eq + Hash> Counter<T> for HashCounter<T> {
+    fn add(&mut self, item: T) -> usize {
+        let count = self.0.entry(item).or_default();
+        *count += 1;
+        *count
+    }
+
+    fn remove(&mut self, item: T) -> usize {
+        match self.0.get_mut(&item) {
+            Some(1) => {
+                self.0.remove(&item);
+                0
+            }
+            Some(count) => {
+                *count -= 1;
+                *count
+            }
+            None => 0,
+        }
+    }
+}
+
+/// Counts small integers in a plain array, every item has to be below the bound it was made with
+#[derive(Debug, Clone)]
+pub struct ArrayCounter<T> {
+    counts: Vec<usize>,
+    item: PhantomData<T>,
+}
+
+impl<T> ArrayCounter<T> {
+    pub fn new(bound: usize) -> Self {
+        ArrayCounter {
+            counts: vec![0; bound],
+            item: PhantomData,
+        }
+    }
+}
+
+impl<T: Into<usize>> Counter<T> for ArrayCounter<T> {
+    fn add(&mut self, item: T) -> usize {
+        let count = &mut self.counts[item.into()];
+        *count += 1;
+        *count
+    }
+
+    fn remove(&mut self, item: T) -> usize {
+        let count = &mut self.counts[item.into()];
+        *count = count.saturating_sub(1);
+        *count
+    }
+}
+
+/// Slides a window of `size` items over a sequence and tells whether the items in it are all
+/// different, or have at most some number of repeats.
+///
+/// ```
+/// use advent_of_code::window::DistinctWindow;
+///
+/// let stream = "mjqjpqmgbljsphdztnvjfqwrcgsmlb";
+/// let start = DistinctWindow::bytes(4).windows(stream.bytes()).next();
+/// assert_eq!(start, Some(3));
+/// ```
+#[derive(Debug, Clone)]
+pub struct DistinctWindow<T, C> {
+    window: VecDeque<T>,
+    counts: C,
+    size: usize,
+    /// number of different items in the window
+    distinct: usize,
+    max_duplicates: usize,
+}
+
+impl<T: Copy + Eq + Hash> DistinctWindow<T, HashCounter<T>> {
+    /// Window over any hashable item
+    pub fn new(size: usize) -> Self {
+        Self::with_counter(size, HashCounter(HashMap::new()))
+    }
+}
+
+impl DistinctWindow<u8, ArrayCounter<u8>> {
+    /// Window over bytes counted in an array, cheaper than hashing them
+    pub fn bytes(size: usize) -> Self {
+        Self::with_counter(size, ArrayCounter::new(256))
+    }
+}
+
+impl<T: Copy + Into<usize>> DistinctWindow<T, ArrayCounter<T>> {
+    /// Window over integers below `bound` counted in an array
+    pub fn small(size: usize, bound: usize) -> Self {
+        Self::with_counter(size, ArrayCounter::new(bound))
+    }
+}
+
+impl<T: Copy, C: Counter<T>> DistinctWindow<T, C> {
+    pub fn with_counter(size: usize, counts: C) -> Self {
+        DistinctWindow {
+            window: VecDeque::with_capacity(size + 1),
+            counts,
+            size,
+            distinct: 0,
+            max_duplicates: 0,
+        }
+    }
+
+    /// Lets up to `max_duplicates` items in the window be repeats of an earlier one
+    pub fn with_max_duplicates(mut self, max_duplicates: usize) -> Self {
+        self.max_duplicates = max_duplicates;
+        self
+    }
+
+    /// Number of items in the window that repeat an earlier one
+    pub fn duplicates(&self) -> usize {
+        self.window.len() - self.distinct
+    }
+
+    /// Slides the window on by `item`. Returns `None` until the window is full, after that whether
+    /// the window has few enough duplicates.
+    ///
+    /// Each push is `O(1)` plus the cost of the counter.
+    pub fn push(&mut self, item: T) -> Option<bool> {
+        self.window.push_back(item);
+        if self.counts.add(item) == 1 {
+            self.distinct += 1;
+        }
+        if self.window.len() > self.size {
+            if let Some(old) = self.window.pop_front() {
+                if self.counts.remove(old) == 0 {
+                    self.distinct -= 1;
+                }
+            }
+        }
+        if self.window.len() < self.size {
+            return None;
+        }
+        Some(self.duplicates() <= self.max_duplicates)
+    }
+
+    /// Index of the first item of every window with few enough duplicates. Works on any stream of
+    /// items, use `.next()` for only the first window.
+    pub fn windows<I: IntoIterator<Item = T>>(self, items: I) -> Windows<I::IntoIter, T, C> {
+        Windows {
+            items: items.into_iter(),
+            detector: self,
+            pushed: 0,
+        }
+    }
+}
+
+pub struct Windows<I, T, C> {
+    items: I,
+    detector: DistinctWindow<T, C>,
+    pushed: usize,
+}
+
+impl<I: Iterator<Item = T>, T: Copy, C: Counter<T>> Iterator for Windows<I, T, C> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        for item in self.items.by_ref() {
+            self.pushed += 1;
+            if self.detector.push(item) == Some(true) {
+                return Some(self.pushed - self.detector.size);
+            }
+        }
+        None
+    }
+}
+
+/// Start of the first window of `size` all different items in `items`
+pub fn first_distinct<T: Copy + Eq + Hash>(items: &[T], size: usize) -> Option<usize> {
+    DistinctWindow::new(size)
+        .windows(items.iter().copied())
+        .next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{first_distinct, DistinctWindow};
+
+    #[test]
+    fn first_window() {
+        let tests = [
+            ("bvwbjplbgvbhsrlpgdmjqwftvncz", 1, 9),
+            ("nppdvjthqldpwncqszvftbrmjlhg", 2, 9),
+            ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 7, 12),
+        ];
+        for (s, four, fourteen) in tests {
+            assert_eq!(
+                DistinctWindow::bytes(4).windows(s.bytes()).next(),
+                Some(four)
+            );
+            assert_eq!(
+                DistinctWindow::new(14).windows(s.chars()).next(),
+                Some(fourteen)
+            );
+        }
+        assert_eq!(first_distinct(&[1, 1, 1], 2), None);
+    }
+
+    #[test]
+    fn every_window() {
+        let items = [1u8, 2, 3, 1, 2, 2, 3, 4];
+        let windows: Vec<usize> = DistinctWindow::small(3, 5).windows(items).collect();
+
+        assert_eq!(windows, vec![0, 1, 2, 5]);
+    }
+
+    #[test]
+    fn allowed_duplicates() {
+        let mut window = DistinctWindow::new(4).with_max_duplicates(1);
+
+        let pushed: Vec<Option<bool>> = "aabcbbb".chars().map(|c| window.push(c)).collect();
+        assert_eq!(
+            pushed,
+            vec![
+                None,
+                None,
+                None,
+                Some(true),
+                Some(true),
+                Some(false),
+                Some(false)
+            ]
+        );
+        assert_eq!(window.duplicates(), 2);
+    }
+}